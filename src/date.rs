@@ -1,15 +1,22 @@
-///Date utils
-///Mostly for day counting between dates
+//!Date utils
+//!Mostly for day counting between dates
+//!
+//! There are many ways to count dates; here I have chosen:
+//! - Gregorian date from 1582 AD onwards
+//! - Julian date before that (Proleptic Julian Calendar)
+//! - No year 0
+//!
+//! It is more interesting from a computational point of view!
+//!
+//! Dates only: there is no time of day, time zone or daylight saving time here.
+//! Parsers drop the time part of ISO strings, and timestamps are floored to their UTC day
 
-/// There are many ways to count dates; here I have chosen:
-/// - Gregorian date from 1582 AD onwards
-/// - Julian date before that (Proleptic Julian Calendar)
-/// - No year 0
-/// It is more interesting from a computational point of view!
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display, Formatter};
 
-use std::fmt::{Display, Formatter};
-
-#[derive(Debug)]
+/// Dates are ordered chronologically, as fields are compared from year to day
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Date {
     year: i32,
     month: u8,
@@ -19,14 +26,220 @@ pub struct Date {
 impl Display for Date {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let era = if self.year < 0 { " BC" } else { "" }; //less verbose than " AD"
-        write!(
-            f, "{} {}, {}{}", MONTHS[self.month as usize - 1],
-            self.day, self.year.abs(), era)
+        //pad() honours width, fill and alignment flags
+        f.pad(&format!(
+            "{} {}, {}{}", self.month_name().unwrap_or("<invalid month>"),
+            self.day, self.year.unsigned_abs(), era))
+    }
+}
+
+/// Short and unambiguous for test failures: *Date(2001-02-28)*, or *Date(-0044-03-15)* for BC years
+impl Debug for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Date({}-{:02}-{:02})", Date::year_label(self.year), self.month, self.day)
+    }
+}
+
+/// Compare with a (year, month, day) tuple, as in *assert_eq!(date, (2001, 2, 28))*
+impl PartialEq<(i32, u8, u8)> for Date {
+    fn eq(&self, other: &(i32, u8, u8)) -> bool {
+        self.to_tuple() == *other
+    }
+}
+
+impl From<Date> for (i32, u8, u8) {
+    fn from(date: Date) -> Self {
+        date.to_tuple()
+    }
+}
+
+/// Only valid dates are accepted, as in *try_new*
+impl TryFrom<(i32, u8, u8)> for Date {
+    type Error = DateError;
+
+    fn try_from(tuple: (i32, u8, u8)) -> Result<Self, Self::Error> {
+        Date::try_from_tuple(tuple)
+    }
+}
+
+/// Why a *Date* is not valid. *SkippedByReform* is a real calendar day that history skipped;
+/// the other variants could never be a date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    YearZero,
+    InvalidMonth,
+    /// One of the days skipped by the Gregorian reform, with the valid days around the gap
+    SkippedByReform { date: Date, previous: Date, next: Date },
+    InvalidDay(Date),
+}
+
+impl DateError {
+    /// Whether the date could never exist, rather than being lost in the Gregorian reform
+    pub fn is_structural(&self) -> bool {
+        !matches!(self, DateError::SkippedByReform { .. })
+    }
+}
+
+impl Display for DateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateError::YearZero => write!(f, "Year 0 does not exist"),
+            DateError::InvalidMonth => write!(f, "Invalid month"),
+            DateError::SkippedByReform { date, .. } => write!(f, "{} does not exist", date),
+            DateError::InvalidDay(date) => write!(f, "{}: Invalid day", date),
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+/// Why a string could not be parsed into a *Date*, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Offset of the offending character, counted in characters from 0
+    pub position: usize,
+}
+
+impl ParseError {
+    /// An error at byte *offset* of the parsed string *s*
+    fn at(s: &str, offset: usize, message: String) -> ParseError {
+        let position = s.get(..offset).map_or(offset, |parsed| parsed.chars().count());
+        ParseError { message, position }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at character {})", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// So *ParseError*s can be propagated with *?* from functions returning *String* errors
+impl From<ParseError> for String {
+    fn from(error: ParseError) -> Self {
+        error.to_string()
+    }
+}
+
+/// So *DateError*s can be propagated with *?* from functions returning *String* errors
+impl From<DateError> for String {
+    fn from(error: DateError) -> Self {
+        error.to_string()
+    }
+}
+
+/// When a country switched from the Julian to the Gregorian calendar.
+/// Dates are always computed with the 1582 reform; a *Calendar* only tells which of them
+/// never happened somewhere else because that country skipped them later
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calendar {
+    /// Last day of the Julian calendar
+    julian_end: Date,
+    /// First day of the Gregorian calendar
+    gregorian_start: Date,
+}
+
+impl Calendar {
+    /// The papal reform of 1582, followed by Italy, Spain, Portugal and Poland
+    pub const ROME: Calendar = Calendar {
+        julian_end: Date { year: 1582, month: 10, day: 4 },
+        gregorian_start: Date { year: 1582, month: 10, day: 15 },
+    };
+    /// Great Britain and its colonies switched in 1752
+    pub const BRITAIN: Calendar = Calendar {
+        julian_end: Date { year: 1752, month: 9, day: 2 },
+        gregorian_start: Date { year: 1752, month: 9, day: 14 },
+    };
+
+    /// Whether *date* falls in the days this calendar skipped
+    fn skips(&self, date: &Date) -> bool {
+        self.julian_end < *date && *date < self.gregorian_start
+    }
+}
+
+/// The days lost in a Gregorian reform, and the valid days around them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReformInfo {
+    /// Last day of the Julian calendar, right before the gap
+    pub last_julian: Date,
+    /// First day of the gap
+    pub first_skipped: Date,
+    /// Last day of the gap
+    pub last_skipped: Date,
+    /// First day of the Gregorian calendar, right after the gap
+    pub first_gregorian: Date,
+    /// How many days were skipped
+    pub skipped_days: u8,
+}
+
+/// Signed difference between two dates, see *Date::checked_sub*
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateDelta {
+    /// Whole difference in days
+    pub total_days: i64,
+    pub years: i32,
+    /// Months past the whole years, up to 11
+    pub months: i32,
+    /// Days past the whole months
+    pub days: i32,
+}
+
+/// Tells which dates are holidays, for business day arithmetic
+pub trait HolidayProvider {
+    fn is_holiday(&self, date: &Date) -> bool;
+}
+
+/// A fixed list of holidays
+impl HolidayProvider for Vec<Date> {
+    fn is_holiday(&self, date: &Date) -> bool {
+        self.contains(date)
+    }
+}
+
+/// So ranges of dates can be iterated over on nightly, as in *for date in start..end*.
+/// Steps are days that happened, so October 4, 1582 is followed by October 15
+#[cfg(feature = "step")]
+impl std::iter::Step for Date {
+    fn steps_between(start: &Self, end: &Self) -> (usize, Option<usize>) {
+        if start.is_valid().is_err() || end.is_valid().is_err() || start > end {
+            return (0, None);
+        }
+        match usize::try_from(end.julian_day_number() - start.julian_day_number()) {
+            Ok(steps) => (steps, Some(steps)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    fn forward_checked(start: Self, count: usize) -> Option<Self> {
+        start.checked_add_days(i64::try_from(count).ok()?)
+    }
+
+    fn backward_checked(start: Self, count: usize) -> Option<Self> {
+        start.checked_add_days(-i64::try_from(count).ok()?)
+    }
+}
+
+/// The default *Date* is the Unix epoch, January 1, 1970
+impl Default for Date {
+    fn default() -> Self {
+        Date::new(1970, 1, 1)
     }
 }
 
 const GREGORIAN_YEAR: i32 = 1582;
-static MONTH_DAYS: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+/// Julian Day Number of the Unix epoch, January 1, 1970
+const UNIX_EPOCH_JULIAN_DAY: i64 = 2_440_588;
+/// Julian Day Number of October 15, 1582, the first Gregorian day
+const GREGORIAN_JULIAN_DAY: i64 = 2_299_161;
+/// Julian Day Number of the day before January 1, 1 AD, so that date is Rata Die day 1
+const RATA_DIE_OFFSET: i64 = 1_721_423;
+/// Julian Day Number of March 1st, 0 (1 BC) in each calendar, origin of their day counts
+const JULIAN_CALENDAR_OFFSET: i64 = 1_721_118;
+const GREGORIAN_CALENDAR_OFFSET: i64 = 1_721_120;
+static MONTH_DAYS: [u8; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 static RUNNING_DAYS_PER_MONTH: [u32; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
 pub static MONTHS: [&str; 12] = [
     "January",
@@ -43,41 +256,453 @@ pub static MONTHS: [&str; 12] = [
     "December"
 ];
 
+pub static WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday"
+];
+
+/// Month names in some language. Missing names are looked up in the fallback locale,
+/// if any, and in *MONTHS* at last, so partial translations never print blanks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    months: [Option<&'static str>; 12],
+    fallback: Option<Box<Locale>>,
+}
+
+impl Locale {
+    /// A locale with the given month names, January first; *None* entries fall back
+    pub fn new(months: [Option<&'static str>; 12]) -> Locale {
+        Locale { months, fallback: None }
+    }
+
+    /// This locale, looking up its missing names in *fallback* before English
+    pub fn with_fallback(self, fallback: Locale) -> Locale {
+        Locale { fallback: Some(Box::new(fallback)), ..self }
+    }
+
+    /// Name of *month* (1 to 12), or *None* if the month is out of range
+    pub fn month_name(&self, month: u8) -> Option<&'static str> {
+        let index = (month as usize).checked_sub(1)?;
+        match self.months.get(index)? {
+            Some(name) => Some(name),
+            None => match &self.fallback {
+                Some(fallback) => fallback.month_name(month),
+                None => Some(MONTHS[index]),
+            },
+        }
+    }
+}
+
+/// Days of the week, from Monday as in ISO 8601
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// Before or after the start of the Christian era; there is no year 0 between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Era {
+    Bc,
+    Ad,
+}
+
+/// Periods dates can be grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    /// ISO 8601 week, starting on Monday
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+/// Units a span between dates can be measured in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationUnit {
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+/// What range iteration does with the days lost in the Gregorian reform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RangeMode {
+    /// Go straight from October 4 to October 15, 1582
+    #[default]
+    Skip,
+    /// Yield a *RangeItem::Gap* between them
+    Gap,
+}
+
+/// Each step of *Date::range_with_mode*
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeItem {
+    Day(Date),
+    /// The days lost in the Gregorian reform, first and last included
+    Gap { first: Date, last: Date },
+}
+
+/// Rules a year, month and day are read with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarSystem {
+    /// Julian before October 15, 1582 and Gregorian from then on, as everywhere in this module
+    Hybrid,
+    /// Gregorian rules for every date, even before 1582
+    ProlepticGregorian,
+}
+
+/// What to do when a day does not exist in the target month, as January 31 in February
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayPolicy {
+    /// Use the last day of the month
+    Clamp,
+    /// Fail
+    Skip,
+    /// Roll the extra days into the following month
+    Overflow,
+}
+
+/// Months of the year
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Month {
+    January = 1,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+impl Month {
+    /// Month number, from 1 (January) to 12 (December)
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+
+    /// English name of the month
+    pub fn name(self) -> &'static str {
+        MONTHS[self as usize - 1]
+    }
+
+    /// How many days this month has in *year*, including leap days and the 10 days
+    /// October 1582 lost in the Gregorian reform; 0 for the missing year 0
+    pub fn days_in(self, year: i32) -> u32 {
+        Date::month_length(year, self as u8).map_or(0, u32::from)
+    }
+}
+
+/// A Julian Date: days elapsed since noon of January 1, 4713 BC (proleptic Julian calendar),
+/// including the day fraction. Whole values fall at noon, so midnight is at *.5*
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct JulianDate(pub f64);
+
+impl JulianDate {
+    /// The Julian Date *days* later (or earlier); adding 0.5 to a noon goes to the next midnight
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(self, days: f64) -> JulianDate {
+        JulianDate(self.0 + days)
+    }
+
+    /// Julian Day Number of the civil day (starting at midnight) this Julian Date falls in
+    pub fn julian_day(self) -> i64 {
+        (self.0 + 0.5).floor() as i64
+    }
+
+    /// Fraction of the civil day elapsed since midnight, in [0, 1)
+    pub fn day_fraction(self) -> f64 {
+        let shifted = self.0 + 0.5;
+        shifted - shifted.floor()
+    }
+
+    /// Civil *Date* this Julian Date falls in; NaN and infinities have none
+    pub fn to_date(self) -> Result<Date, String> {
+        if !self.0.is_finite() {
+            return Err(format!("{}: Invalid Julian Date", self.0));
+        }
+        Date::from_julian_day(self.julian_day())
+    }
+}
+
+/// Ordinal of a day within its year, from 1 (January 1st) to the length of the year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DayOfYear(u16);
+
+impl DayOfYear {
+    /// The *ordinal*th day of *year*, checking the year has that many days
+    pub fn new(year: i32, ordinal: u16) -> Result<DayOfYear, String> {
+        Date::new(year, 1, 1).is_valid()?;
+        if ordinal < 1 || ordinal as i32 > Date::year_days(year) {
+            return Err(format!("{}: Invalid day of year {}", ordinal, year));
+        }
+        Ok(DayOfYear(ordinal))
+    }
+
+    /// The ordinal, from 1
+    pub fn get(self) -> u16 {
+        self.0
+    }
+
+    /// The *Date* with this ordinal in *year*, which may be shorter than the one it was built for
+    pub fn to_date(self, year: i32) -> Result<Date, String> {
+        DayOfYear::new(year, self.0)?;
+        Ok(Date::from_day_of_year(year, self.0 as i32))
+    }
+}
+
+impl Weekday {
+    /// The weekday *days* days after a Monday (negative counts go backwards)
+    fn from_monday(days: i32) -> Weekday {
+        match days.rem_euclid(7) {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    /// How many days this weekday comes after Monday
+    fn days_from_monday(self) -> i32 {
+        self as i32
+    }
+
+    /// English name of the weekday
+    pub fn name(self) -> &'static str {
+        WEEKDAYS[self as usize]
+    }
+
+    /// Three-letter abbreviation of the weekday, such as "Thu"
+    pub fn short_name(self) -> &'static str {
+        &self.name()[..3]
+    }
+}
+
 impl Date {
+    /// Earliest representable *Date*
+    pub const MIN: Date = Date { year: i32::MIN, month: 1, day: 1 };
+    /// Latest representable *Date*
+    pub const MAX: Date = Date { year: i32::MAX, month: 12, day: 31 };
+
+    /// First day of the Gregorian calendar, October 15, 1582
+    pub fn gregorian_reform_date() -> Date {
+        Calendar::ROME.gregorian_start
+    }
+
+    /// Last day of the Julian calendar, October 4, 1582
+    pub fn julian_end_date() -> Date {
+        Calendar::ROME.julian_end
+    }
+
+    /// Whether *year* can be the year of a *Date*: it fits in an *i32* and is not 0
+    pub fn is_year_representable(year: i64) -> bool {
+        year != 0 && i32::try_from(year).is_ok()
+    }
+
+    /// Build a valid *Date* from a year computed in *i64*, as in arithmetic that may
+    /// overflow *i32*, reporting years out of range rather than truncating them
+    pub fn try_new_i64(year: i64, month: u8, day: u8) -> Result<Date, String> {
+        let year = i32::try_from(year).map_err(|_| format!("{}: Year out of range", year))?;
+        Ok(Date::try_new(year, month, day)?)
+    }
+
     pub fn new(year: i32, month: u8, day: u8) -> Self {
         Self { year, month, day }
     }
 
+    /// Always false: a *Date* is a whole calendar day, with no time of day to shift, so
+    /// questions such as daylight saving transitions belong to a datetime library.
+    ///
+    /// ```
+    /// # use date::date::Date;
+    /// let date = Date::parse("2021-07-22T13:00:00Z").unwrap();
+    /// assert!(!date.has_time_component());
+    /// assert_eq!(date, Date::new(2021, 7, 22));
+    /// ```
+    pub fn has_time_component(&self) -> bool {
+        false
+    }
+
+    /// Era of the year: BC for negative years, AD otherwise
+    pub fn era(&self) -> Era {
+        if self.year < 0 { Era::Bc } else { Era::Ad }
+    }
+
+    /// Astronomical year number, as ISO 8601 extended years use: 1 BC is 0, 2 BC is -1
+    /// and AD years are unchanged
+    pub fn proleptic_year(&self) -> i32 {
+        if self.year < 0 { self.year + 1 } else { self.year }
+    }
+
+    /// English name of the month, or *None* if the month is out of range
+    pub fn month_name(&self) -> Option<&'static str> {
+        MONTHS.get((self.month as usize).checked_sub(1)?).copied()
+    }
+
+    /// Name of the month in *locale*, or *None* if the month is out of range
+    pub fn localized_month_name(&self, locale: &Locale) -> Option<&'static str> {
+        locale.month_name(self.month)
+    }
+
+    /// Build a *Date*, checking it is valid. Use *new* when the components are known to be good
+    pub fn try_new(year: i32, month: u8, day: u8) -> Result<Date, DateError> {
+        let date = Date::new(year, month, day);
+        date.check()?;
+        Ok(date)
+    }
+
+    /// Build a *Date*, or *None* if it is not valid; *try_new* tells why
+    pub fn checked_from_ymd(year: i32, month: u8, day: u8) -> Option<Date> {
+        Date::try_new(year, month, day).ok()
+    }
+
+    /// The (year, month, day) components
+    pub fn to_tuple(&self) -> (i32, u8, u8) {
+        (self.year, self.month, self.day)
+    }
+
+    /// Build a valid *Date* from (year, month, day) components
+    pub fn try_from_tuple((year, month, day): (i32, u8, u8)) -> Result<Date, DateError> {
+        Date::try_new(year, month, day)
+    }
+
+    /// The (year, day of year) components, day 1 being January 1st. Unlike *day_of_year*
+    /// this returns a *Result* rather than a bare tuple: an invalid *Date*, such as one in
+    /// month 13, has no day of year and gives an error instead of a panic
+    pub fn to_ordinal_tuple(&self) -> Result<(i32, i32), String> {
+        self.is_valid()?;
+        Ok((self.year, self.day_of_year()))
+    }
+
+    /// Build a valid *Date* from (year, day of year) components, see *from_ordinal*
+    pub fn from_ordinal_tuple((year, ordinal): (i32, i32)) -> Result<Date, String> {
+        let ordinal = u16::try_from(ordinal).map_err(|_| format!("{}: Invalid day of year {}", ordinal, year))?;
+        Date::from_ordinal(year, ordinal)
+    }
+
     /// Check if a Date is valid.
-    /// Example of the powerful *match tuple* Rust pattern
+    /// Rules are checked one after the other, so the first one failing is reported:
+    /// year 0, then the month range, then the day range for the month,
+    /// and finally the days lost in the Gregorian reform, so October 40, 1582 is an invalid day
+    /// rather than a skipped one.
+    /// The reform skipped October 5 to 14, 1582, both inclusive: October 4 (Julian) was
+    /// followed by October 15 (Gregorian)
     pub fn is_valid(&self) -> Result<(), String> {
-        match (self.year, self.month, self.day) {
-            (0, _, _) => Err("Year 0 does not exist".into()),
-            (_, month, _) if month < 1 || month > 12 => Err("Invalid month".into()),
-            (GREGORIAN_YEAR, 10, day) if day > 4 && day < 14 
-                => Err(format!("{} does not exist", self)),
-            (year, month, day) => {
-                let mut m = MONTH_DAYS[month as usize - 1];
-                if month == 2 {
-                    m += if Date::is_leap(year) { 1 } else { 0 }
-                }
-                if day > 0 && day <= m as u8 {
-                    Ok(())
-                } else {
-                    Err(format!("{}: Invalid day", self))
-                }
-            }
+        Ok(self.check()?)
+    }
+
+    /// Check raw components as *is_valid* does, before building a *Date* with them
+    pub fn validate(year: i32, month: u8, day: u8) -> Result<(), String> {
+        Date::new(year, month, day).is_valid()
+    }
+
+    /// Check if a Date really happened in the given *calendar*: besides being valid,
+    /// it must not be one of the days the country skipped when it adopted the Gregorian calendar
+    pub fn is_historically_valid(&self, calendar: &Calendar) -> Result<(), String> {
+        self.is_valid()?;
+        if calendar.skips(self) {
+            return Err(format!("{} was skipped by the calendar reform", self));
+        }
+        Ok(())
+    }
+
+    /// The validation rules behind *is_valid* and *try_new*, with an error telling
+    /// impossible dates from those lost in the Gregorian reform
+    pub fn check(&self) -> Result<(), DateError> {
+        if self.year == 0 {
+            return Err(DateError::YearZero);
+        }
+        if !(1..=12).contains(&self.month) {
+            return Err(DateError::InvalidMonth);
+        }
+        if self.day < 1 || self.day > Date::last_day(self.year, self.month) {
+            return Err(DateError::InvalidDay(self.clone()));
+        }
+        if Calendar::ROME.skips(self) {
+            return Err(DateError::SkippedByReform {
+                date: self.clone(),
+                previous: Date::julian_end_date(),
+                next: Date::gregorian_reform_date(),
+            });
         }
+        Ok(())
     }
 
     /// Check if a year is a leap year
     pub fn is_leap(year: i32) -> bool {
         let mut y = year;
-        if y < 0 { y += 1; } //no year 0
+        //no year 0: BC years shift to astronomical numbering, 1 BC -> 0, 4 BC -> -3, 5 BC -> -4,
+        //so the proleptic Julian leap years are 1 BC, 5 BC, 9 BC... and not 4 BC or 8 BC.
+        //Rome actually got it wrong at first, see *is_historical_leap*
+        if y < 0 { y += 1; }
         if y < GREGORIAN_YEAR { return y % 4 == 0; }
         y % 400 == 0 || (y % 4 == 0 && y % 100 != 0)
     }
 
+    /// Check if a year was a leap year as actually observed in Rome.
+    /// After the Julian reform of 45 BC the pontiffs added a leap day every third year,
+    /// until Augustus suspended them to make up for it; this follows Scaliger's reconstruction,
+    /// with leap years 42 BC, 39 BC... 9 BC and none again until 8 AD.
+    /// Other years follow *is_leap*. Dates are always computed with *is_leap*
+    pub fn is_historical_leap(year: i32) -> bool {
+        match year {
+            -45..=-1 => (-42..=-9).contains(&year) && year % 3 == 0,
+            1..=7 => false,
+            _ => Date::is_leap(year),
+        }
+    }
+
+    /// How many days February has in *year*: 29 in leap years, Julian or Gregorian, else 28
+    pub fn february_days(year: i32) -> u32 {
+        if Date::is_leap(year) { 29 } else { 28 }
+    }
+
+    /// Whether *year* has a February 29; the same as *is_leap*, except for the missing year 0
+    pub fn has_leap_day(year: i32) -> bool {
+        year != 0 && Date::is_leap(year)
+    }
+
+    /// February 29 of *year*, or *None* if it is not a leap year
+    pub fn leap_day(year: i32) -> Option<Date> {
+        if Date::has_leap_day(year) { Some(Date::new(year, 2, 29)) } else { None }
+    }
+
+    /// Every leap year from *start_year* to *end_year*, both included, skipping year 0.
+    /// Julian rules apply before 1582 and Gregorian ones from then on
+    pub fn leap_years_in(start_year: i32, end_year: i32) -> Vec<i32> {
+        (start_year..=end_year)
+            .filter(|&year| year != 0 && Date::is_leap(year))
+            .collect()
+    }
+
+    /// Check if a year is a leap year under the Gregorian rules, even before 1582
+    /// (Proleptic Gregorian Calendar)
+    pub fn is_proleptic_gregorian_leap(year: i32) -> bool {
+        let mut y = year;
+        if y < 0 { y += 1; } //no year 0
+        y % 400 == 0 || (y % 4 == 0 && y % 100 != 0)
+    }
+
     /// How many days *year* has
     fn year_days(year: i32) -> i32 {
         if year == 0 { return 0 }
@@ -85,131 +710,3218 @@ impl Date {
         365 + if Date::is_leap(year) { 1 } else { 0 }
     }
 
+    /// How many days the 10 years from *start_year* have, 1582's short year included
+    pub fn days_in_decade(start_year: i32) -> Result<i32, String> {
+        Date::days_in_years(start_year, 10)
+    }
+
+    /// How many days the 100 years from *start_year* have, 1582's short year included
+    pub fn days_in_century(start_year: i32) -> Result<i32, String> {
+        Date::days_in_years(start_year, 100)
+    }
+
+    /// How many days the *count* years from *start_year* have, skipping year 0,
+    /// from the Rata Die numbers of the first days of both ends
+    fn days_in_years(start_year: i32, count: i32) -> Result<i32, String> {
+        let start = Date::new(start_year, 1, 1);
+        start.is_valid()?;
+        let skips_zero = start_year < 0 && start_year + count >= 0;
+        match start_year.checked_add(count + if skips_zero { 1 } else { 0 }) {
+            Some(end_year) => Ok((Date::new(end_year, 1, 1).to_rata_die() - start.to_rata_die()) as i32),
+            None => Err(format!("{} + {} years: Out of range", start_year, count)),
+        }
+    }
+
+    /// How many days *month* has in *year*, from 28 to 31 with leap days included.
+    /// October 1582 has only 21, the Gregorian reform having skipped October 5 to 14
+    pub fn month_length(year: i32, month: u8) -> Result<u8, String> {
+        if year == 0 {
+            return Err(DateError::YearZero.into());
+        }
+        if !(1..=12).contains(&month) {
+            return Err(DateError::InvalidMonth.into());
+        }
+        let days = if month == 2 { Date::february_days(year) as u8 } else { MONTH_DAYS[month as usize - 1] };
+        let skipped = if year == GREGORIAN_YEAR && month == 10 { 10 } else { 0 };
+        Ok(days - skipped)
+    }
+
+    /// Number of the last day of a valid *month*: its *month_length*, but for October 1582,
+    /// which still ends on the 31st
+    fn last_day(year: i32, month: u8) -> u8 {
+        let skipped = if year == GREGORIAN_YEAR && month == 10 { 10 } else { 0 };
+        Date::month_length(year, month).map_or(0, |length| length + skipped)
+    }
+
     /// Count the days a given month for a given year has.
     /// Takes into account not only leap years, but also the Gregorian rift of 1582
     fn month_days(month: u8, year: i32) -> u32 {
-        let mut bias = if month > 2 && Date::is_leap(year) { 1 } else { 0 };
-        if year == GREGORIAN_YEAR && month > 9 { bias -= 10 }
-        RUNNING_DAYS_PER_MONTH[month as usize - 1] + bias
+        let mut days = RUNNING_DAYS_PER_MONTH[month as usize - 1];
+        if month > 2 && Date::is_leap(year) { days += 1 }
+        if year == GREGORIAN_YEAR && month > 10 { days -= 10 }
+        days
     }
 
-    /// How many days have passed from Jan 1st of the given *Date*'s year
+    /// Ordinal of the given *Date* within its year (Jan 1st is day 1)
     pub fn day_of_year(&self) -> i32 {
         let mut days = Date::month_days(self.month, self.year) as i32;
-        if self.year == GREGORIAN_YEAR && (self.month > 10
-            || (self.month == 10 && self.day > 13) ) {
+        if self.year == GREGORIAN_YEAR && self.month == 10 && self.day > 14 {
             days -= 10;
         }
         days + self.day as i32
     }
 
-    /// How many days there are between two given *Date*s
-    pub fn days_between_dates(first: &Date, last: &Date) -> Result<i32, String> {
-        if let Err(error) = first.is_valid() {
-            return Err(error);
+    /// How many days have passed from Jan 1st of the given *Date*'s year (0 on Jan 1st).
+    /// A *Result* rather than a bare *i32*, so an invalid *Date* gives an error, not a panic
+    pub fn days_since_start_of_year(&self) -> Result<i32, String> {
+        self.is_valid()?;
+        Ok(self.day_of_year() - 1)
+    }
+
+    /// Fraction of the year elapsed by the start of this *Date*, from 0.0 on January 1
+    /// to just under 1.0 on December 31, for plotting dates on a continuous axis
+    pub fn year_fraction(&self) -> Result<f64, String> {
+        self.is_valid()?;
+        Ok(self.days_since_start_of_year()? as f64 / Date::year_days(self.year) as f64)
+    }
+
+    /// Next year, skipping year 0
+    fn next_year(year: i32) -> i32 {
+        if year == -1 { 1 } else { year + 1 }
+    }
+
+    /// Previous year, skipping year 0
+    fn previous_year(year: i32) -> i32 {
+        if year == 1 { -1 } else { year - 1 }
+    }
+
+    /// Inverse of *day_of_year*: the *Date* with the given ordinal within *year*
+    fn from_day_of_year(year: i32, ordinal: i32) -> Date {
+        let mut month = 12;
+        while month > 1 && Date::month_days(month, year) as i32 >= ordinal {
+            month -= 1;
         }
-        if let Err(error) = last.is_valid() {
-            return Err(error);
+        let mut day = ordinal - Date::month_days(month, year) as i32;
+        if year == GREGORIAN_YEAR && month == 10 && day > 4 {
+            day += 10;
         }
+        Date::new(year, month, day as u8)
+    }
 
-        let mut days = 0;
-        let (year1, year2) = if first.year > last.year {
-            (last.year, first.year)
-        } else {
-            (first.year, last.year)
-        };
-        for year in year1..year2 {
-            days += Date::year_days(year);
+    /// The *ordinal*th day of *year*, from 1 (January 1st); 1582 has only 355
+    pub fn from_ordinal(year: i32, ordinal: u16) -> Result<Date, String> {
+        DayOfYear::new(year, ordinal)?.to_date(year)
+    }
+
+    /// The *Date* with the given ordinal within *year*, wrapping around the year when the
+    /// ordinal falls outside it: in a 365-day year 366 is January 1 and 0 is December 31.
+    /// The year never changes; 1582 wraps after its 355 days
+    pub fn wrap_day_of_year(year: i32, ordinal: i32) -> Result<Date, String> {
+        Date::new(year, 1, 1).is_valid()?;
+        let ordinal = (ordinal as i64 - 1).rem_euclid(Date::year_days(year) as i64) + 1;
+        Ok(Date::from_day_of_year(year, ordinal as i32))
+    }
+
+    /// The *Date* that falls *days* days after this one (or before, if negative).
+    /// Skips year 0 and the days lost in the Gregorian reform
+    pub fn add_days(&self, days: i32) -> Result<Date, String> {
+        self.is_valid()?;
+        let mut year = self.year;
+        let out_of_range = || format!("{} + {} days: Out of range", self, days);
+        let mut ordinal = self.day_of_year().checked_add(days).ok_or_else(out_of_range)?;
+        while ordinal > Date::year_days(year) {
+            if year == i32::MAX {
+                return Err(out_of_range());
+            }
+            ordinal -= Date::year_days(year);
+            year = Date::next_year(year);
         }
-        let d1 = first.day_of_year();
-        let d2 = last.day_of_year();
-        if first.year > last.year {
-            Ok(-days - d1 + d2)
+        while ordinal < 1 {
+            if year == i32::MIN {
+                return Err(out_of_range());
+            }
+            year = Date::previous_year(year);
+            ordinal += Date::year_days(year);
+        }
+        Ok(Date::from_day_of_year(year, ordinal))
+    }
+
+    /// *add_days* counting only the days *calendar* kept, so under *Calendar::BRITAIN*
+    /// the day after September 2, 1752 is September 14. With *Calendar::ROME* it is *add_days*
+    pub fn add_days_in(&self, days: i32, calendar: &Calendar) -> Result<Date, String> {
+        self.is_historically_valid(calendar)?;
+        let gap = Date::days_between_dates(&calendar.julian_end, &calendar.gregorian_start)? - 1;
+        let date = self.add_days(days)?;
+        if *self <= calendar.julian_end && date > calendar.julian_end {
+            date.add_days(gap)
+        } else if *self >= calendar.gregorian_start && date < calendar.gregorian_start {
+            date.add_days(-gap)
         } else {
-            Ok(days - d1 + d2)
+            Ok(date)
         }
     }
-}
 
-#[test]
-fn test_leap_years() {
-    assert_eq!(Date::is_leap(2000),true);
-    assert_eq!(Date::is_leap(2001),false);
-    assert_eq!(Date::is_leap(2100),false);
-    assert_eq!(Date::is_leap(2004),true);
-    assert_eq!(Date::is_leap(100),true);
-    assert_eq!(Date::is_leap(-1),true);
-    assert_eq!(Date::is_leap(-4),false);
-}
+    /// Julian Day Number: the days elapsed since January 1, 4713 BC (proleptic Julian calendar)
+    pub fn to_julian_day(&self) -> Result<i64, String> {
+        self.is_valid()?;
+        Ok(self.julian_day_number())
+    }
 
-#[test]
-fn test_invalid_dates() {
-    let date = Date::new(1979, 2, 29);
-    assert!(date.is_valid().is_err());
+    /// The *Date* with the given Julian Day Number
+    pub fn from_julian_day(julian_day: i64) -> Result<Date, String> {
+        Date::from_julian_day_number(julian_day)
+            .ok_or_else(|| format!("{}: Julian day out of range", julian_day))
+    }
 
-    let date = Date::new(1979, 1, 0);
-    assert!(date.is_valid().is_err());
+    /// Parse a Julian Day Number, such as "2451545" for January 1, 2000
+    pub fn parse_julian_day(s: &str) -> Result<Date, ParseError> {
+        Date::trimmed(s, Date::julian_day_date)
+    }
 
-    let date = Date::new(0, 1, 1);
-    assert!(date.is_valid().is_err());
+    fn julian_day_date(s: &str) -> Result<Date, ParseError> {
+        let julian_day = s.parse::<i64>().map_err(|_| {
+            let unsigned = s.strip_prefix(|c| c == '-' || c == '+').unwrap_or(s);
+            let digits = unsigned.bytes().take_while(|b| b.is_ascii_digit()).count();
+            //all digits means the number is too large: blame it whole
+            let position = if digits == unsigned.len() && digits > 0 {
+                0
+            } else {
+                s.len() - unsigned.len() + digits
+            };
+            ParseError::at(s, position, format!("{}: Invalid Julian day", s))
+        })?;
+        Date::from_julian_day(julian_day).map_err(|error| ParseError::at(s, 0, error))
+    }
 
-    let date = Date::new(1, 1, 41);
-    assert!(date.is_valid().is_err());
+    /// Rata Die: serial day number counting January 1, 1 AD of this calendar as day 1.
+    /// Only meaningful for valid dates
+    pub fn to_rata_die(&self) -> i64 {
+        self.julian_day_number() - RATA_DIE_OFFSET
+    }
 
-    let date = Date::new(-4, 15, 1);
-    assert!(date.is_valid().is_err());
+    /// The *Date* with the given Rata Die serial day number
+    pub fn from_rata_die(rata_die: i64) -> Result<Date, String> {
+        rata_die.checked_add(RATA_DIE_OFFSET)
+            .and_then(Date::from_julian_day_number)
+            .ok_or_else(|| format!("{}: Rata Die out of range", rata_die))
+    }
 
-    let date = Date::new(1582, 10, 11);
-    assert!(date.is_valid().is_err());
+    /// Compact, sortable key for storage: its Rata Die number, so serials order as dates do.
+    /// Only meaningful for valid dates
+    pub fn to_serial(&self) -> i64 {
+        self.to_rata_die()
+    }
+
+    /// Inverse of *to_serial*
+    pub fn from_serial(serial: i64) -> Result<Date, String> {
+        Date::from_rata_die(serial)
+    }
+
+    /// Closed-form Julian Day Number of a valid *Date*.
+    /// Years are counted from March so the leap day falls at the end of each cycle
+    fn julian_day_number(&self) -> i64 {
+        if (self.year, self.month, self.day) < (GREGORIAN_YEAR, 10, 15) {
+            let (year, day_of_year) = self.march_year_and_day();
+            let cycle = year.div_euclid(4);
+            let year_of_cycle = year - cycle * 4;
+            JULIAN_CALENDAR_OFFSET + cycle * 1461 + year_of_cycle * 365 + day_of_year
+        } else {
+            self.gregorian_day_number()
+        }
+    }
+
+    /// Julian Day Number of this *Date* read as a proleptic Gregorian date, even before 1582
+    fn gregorian_day_number(&self) -> i64 {
+        let (year, day_of_year) = self.march_year_and_day();
+        let cycle = year.div_euclid(400);
+        let year_of_cycle = year - cycle * 400;
+        GREGORIAN_CALENDAR_OFFSET + cycle * 146_097 + year_of_cycle * 365
+            + year_of_cycle / 4 - year_of_cycle / 100 + day_of_year
+    }
+
+    /// Astronomical year starting on March 1st and the day within it (0 on March 1st)
+    fn march_year_and_day(&self) -> (i64, i64) {
+        let astronomical = self.proleptic_year() as i64;
+        let year = if self.month <= 2 { astronomical - 1 } else { astronomical };
+        let month = if self.month <= 2 { self.month + 9 } else { self.month - 3 } as i64;
+        (year, (153 * month + 2) / 5 + self.day as i64 - 1)
+    }
+
+    /// Compare two dates by the day they name, each read in its own calendar system,
+    /// so October 4, 1582 (hybrid, thus Julian) equals October 14, 1582 (proleptic Gregorian)
+    pub fn compare_absolute(&self, other: &Date, self_system: CalendarSystem,
+                            other_system: CalendarSystem) -> Ordering {
+        self.day_number_in(self_system).cmp(&other.day_number_in(other_system))
+    }
+
+    /// Julian Day Number of this *Date* read in *system*
+    fn day_number_in(&self, system: CalendarSystem) -> i64 {
+        match system {
+            CalendarSystem::Hybrid => self.julian_day_number(),
+            CalendarSystem::ProlepticGregorian => self.gregorian_day_number(),
+        }
+    }
+
+    /// Inverse of *julian_day_number*, or *None* if the year does not fit in an *i32*
+    fn from_julian_day_number(julian_day: i64) -> Option<Date> {
+        let (year, day_of_year) = if julian_day < GREGORIAN_JULIAN_DAY {
+            let days = julian_day.checked_sub(JULIAN_CALENDAR_OFFSET)?;
+            let cycle = days.div_euclid(1461);
+            let day_of_cycle = days - cycle * 1461;
+            let year_of_cycle = (day_of_cycle / 365).min(3);
+            (cycle * 4 + year_of_cycle, day_of_cycle - year_of_cycle * 365)
+        } else {
+            let days = julian_day.checked_sub(GREGORIAN_CALENDAR_OFFSET)?;
+            let cycle = days.div_euclid(146_097);
+            let day_of_cycle = days - cycle * 146_097;
+            let year_of_cycle = (day_of_cycle - day_of_cycle / 1460 + day_of_cycle / 36_524
+                - day_of_cycle / 146_096) / 365;
+            let day_of_year = day_of_cycle
+                - (365 * year_of_cycle + year_of_cycle / 4 - year_of_cycle / 100);
+            (cycle * 400 + year_of_cycle, day_of_year)
+        };
+        let month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month + 2) / 5 + 1;
+        let (astronomical, month) = if month < 10 { (year, month + 3) } else { (year + 1, month - 9) };
+        let year = if astronomical <= 0 { astronomical - 1 } else { astronomical };
+        Some(Date::new(i32::try_from(year).ok()?, month as u8, day as u8))
+    }
+
+    /// The *Date* that falls *days* days after this one (or before, if negative),
+    /// or *None* if it is invalid or the result lies beyond *MIN* or *MAX*
+    pub fn checked_add_days(&self, days: i64) -> Option<Date> {
+        self.is_valid().ok()?;
+        Date::from_julian_day_number(self.julian_day_number().checked_add(days)?)
+    }
+
+    /// *add_days* for offsets beyond *i32*, going through the Rata Die day count.
+    /// Fails past *MIN* or *MAX* instead of overflowing
+    pub fn add_days_i64(&self, days: i64) -> Result<Date, String> {
+        self.is_valid()?;
+        match self.to_rata_die().checked_add(days) {
+            Some(rata_die) => Date::from_rata_die(rata_die)
+                .map_err(|_| format!("{} + {} days: Out of range", self, days)),
+            None => Err(format!("{} + {} days: Out of range", self, days)),
+        }
+    }
+
+    /// The *Date* *days* days after the Unix epoch (before it, if negative)
+    pub fn from_unix_days(days: i64) -> Result<Date, String> {
+        days.checked_add(UNIX_EPOCH_JULIAN_DAY)
+            .and_then(Date::from_julian_day_number)
+            .ok_or_else(|| format!("{}: Unix day out of range", days))
+    }
+
+    /// The *Date* of a Unix timestamp, in UTC.
+    /// Seconds are floored to whole days, so -1 is December 31, 1969 rather than the epoch
+    pub fn from_unix_seconds(secs: i64) -> Result<Date, String> {
+        Date::from_unix_days(secs.div_euclid(86_400))
+    }
+
+    /// The *Date* of a *SystemTime*, in UTC, such as a file modification time.
+    /// Times before the epoch are floored, as in *from_unix_seconds*
+    #[cfg(feature = "clock")]
+    pub fn from_system_time(time: std::time::SystemTime) -> Result<Date, String> {
+        let secs = match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(after) => i64::try_from(after.as_secs()).ok(),
+            Err(before) => {
+                let before = before.duration();
+                let partial = if before.subsec_nanos() > 0 { 1 } else { 0 };
+                i64::try_from(before.as_secs()).ok().map(|secs| -secs - partial)
+            }
+        };
+        secs.ok_or_else(|| "System time out of range".to_string())
+            .and_then(Date::from_unix_seconds)
+    }
+
+    /// The same day as a *time::Date*. The *time* crate is proleptic Gregorian, so only dates
+    /// from the 1582 reform on share their year, month and day with it; earlier dates, invalid
+    /// ones and years *time* cannot represent give None rather than a relabelled day
+    #[cfg(feature = "time")]
+    pub fn to_time_date(&self) -> Option<time::Date> {
+        if self.is_valid().is_err() || *self < Date::gregorian_reform_date() {
+            return None;
+        }
+        let month = time::Month::try_from(self.month).ok()?;
+        time::Date::from_calendar_date(self.year, month, self.day).ok()
+    }
+
+    /// The *Date* of a *time::Date*, or None before the 1582 reform, see *to_time_date*
+    #[cfg(feature = "time")]
+    pub fn from_time_date(date: time::Date) -> Option<Date> {
+        let date = Date::new(date.year(), date.month() as u8, date.day());
+        if date < Date::gregorian_reform_date() { None } else { Some(date) }
+    }
+
+    /// Julian Date at noon of this *Date*, which is its Julian Day Number with no fraction
+    pub fn to_julian_date_noon(&self) -> Result<JulianDate, String> {
+        Ok(JulianDate(self.to_julian_day()? as f64))
+    }
+
+    /// Julian Date at the midnight this *Date* starts with, half a day before noon
+    pub fn to_julian_date_midnight(&self) -> Result<JulianDate, String> {
+        Ok(self.to_julian_date_noon()?.add(-0.5))
+    }
+
+    /// The next day, or *None* past *MAX* or for an invalid *Date*
+    pub fn succ(&self) -> Option<Date> {
+        self.checked_add_days(1)
+    }
+
+    /// The previous day, or *None* before *MIN* or for an invalid *Date*
+    pub fn pred(&self) -> Option<Date> {
+        self.checked_add_days(-1)
+    }
+
+    /// Whether this *Date* comes before *other*
+    pub fn is_before(&self, other: &Date) -> bool {
+        self < other
+    }
+
+    /// Whether this *Date* comes after *other*
+    pub fn is_after(&self, other: &Date) -> bool {
+        self > other
+    }
+
+    /// Whether this *Date* falls from *start* to *end*, both included
+    pub fn is_between(&self, start: &Date, end: &Date) -> bool {
+        start <= self && self <= end
+    }
+
+    /// Iterate over the dates from *start* (included) to *end* (excluded), one day at a time.
+    /// The days lost in the Gregorian reform are skipped. Both dates should be valid
+    pub fn range(start: Date, end: Date) -> impl Iterator<Item = Date> {
+        std::iter::successors(Some(start), Date::succ)
+            .take_while(move |date| *date < end)
+    }
+
+    /// Like *range*, but with *RangeMode::Gap* the days lost in the Gregorian reform are yielded
+    /// as a single *RangeItem::Gap* when the range crosses them
+    pub fn range_with_mode(start: Date, end: Date, mode: RangeMode) -> impl Iterator<Item = RangeItem> {
+        let crosses_gap = start < Date::gregorian_reform_date();
+        Date::range(start, end).flat_map(move |date| {
+            let gap = match date.reform_info() {
+                Some(info) if mode == RangeMode::Gap && crosses_gap && date == info.first_gregorian => {
+                    Some(RangeItem::Gap { first: info.first_skipped, last: info.last_skipped })
+                }
+                _ => None,
+            };
+            gap.into_iter().chain(std::iter::once(RangeItem::Day(date)))
+        })
+    }
+
+    /// Iterate over the dates from *start* to *end*, both included.
+    /// The days lost in the Gregorian reform are skipped. Both dates should be valid
+    pub fn range_inclusive(start: Date, end: Date) -> impl Iterator<Item = Date> {
+        std::iter::successors(Some(start), Date::succ)
+            .take_while(move |date| *date <= end)
+    }
+
+    /// Every date of *year* in order, skipping the days lost in the Gregorian reform
+    pub fn calendar_year(year: i32) -> Result<Vec<Date>, String> {
+        let first = Date::new(year, 1, 1);
+        first.is_valid()?;
+        Ok(std::iter::successors(Some(first), Date::succ)
+            .take_while(|date| date.year == year)
+            .collect())
+    }
+
+    /// Every date of *month* in *year* in order, skipping the days lost in the Gregorian reform
+    pub fn days_of_month(year: i32, month: u8) -> Result<impl Iterator<Item = Date>, String> {
+        let first = Date::new(year, month, 1);
+        first.is_valid()?;
+        Ok(std::iter::successors(Some(first), Date::succ)
+            .take_while(move |date| date.month == month && date.year == year))
+    }
+
+    /// Count the dates from *start* (included) to *end* (excluded) satisfying *pred*
+    pub fn count_matching<F: Fn(&Date) -> bool>(start: Date, end: Date, pred: F) -> Result<u32, String> {
+        start.is_valid()?;
+        end.is_valid()?;
+        Ok(Date::range(start, end).filter(|date| pred(date)).count() as u32)
+    }
+
+    /// Count the *which* days from *first* (included) to *last* (excluded), as *count_matching* does
+    pub fn count_weekday_between(first: &Date, last: &Date, which: Weekday) -> Result<u32, String> {
+        Date::count_matching(first.clone(), last.clone(), |date| date.weekday_unchecked() == which)
+    }
+
+    /// Count the Sundays from *first* (included) to *last* (excluded)
+    pub fn count_sundays_between(first: &Date, last: &Date) -> Result<u32, String> {
+        Date::count_weekday_between(first, last, Weekday::Sunday)
+    }
+
+    /// Count the Saturdays from *first* (included) to *last* (excluded)
+    pub fn count_saturdays_between(first: &Date, last: &Date) -> Result<u32, String> {
+        Date::count_weekday_between(first, last, Weekday::Saturday)
+    }
+
+    /// Weekday of the Unix epoch, January 1, 1970, the anchor every weekday is counted from.
+    /// It was a Thursday: the first Monday of 1970 was January 5, as any almanac shows
+    pub fn epoch_weekday() -> Weekday {
+        Weekday::Thursday
+    }
+
+    /// Day of the week, counted from the Unix epoch
+    pub fn weekday(&self) -> Result<Weekday, String> {
+        self.is_valid()?;
+        Ok(self.weekday_unchecked())
+    }
+
+    /// *weekday* in constant time, counting Julian days from the Unix epoch, for a *Date* known to be valid
+    fn weekday_unchecked(&self) -> Weekday {
+        let days = self.julian_day_number() - UNIX_EPOCH_JULIAN_DAY;
+        let epoch = Date::epoch_weekday().days_from_monday() as i64;
+        Weekday::from_monday((days + epoch).rem_euclid(7) as i32)
+    }
+
+    /// English name of the day of the week, such as "Thursday"
+    pub fn weekday_name(&self) -> Result<&'static str, String> {
+        Ok(self.weekday()?.name())
+    }
+
+    /// Three-letter abbreviation of the day of the week, such as "Thu"
+    pub fn weekday_short_name(&self) -> Result<&'static str, String> {
+        Ok(self.weekday()?.short_name())
+    }
+
+    /// First day of the week containing this *Date*, for weeks starting on *week_start*
+    pub fn start_of_week(&self, week_start: Weekday) -> Result<Date, String> {
+        let offset = self.weekday()?.days_from_monday() - week_start.days_from_monday();
+        self.add_days(-offset.rem_euclid(7))
+    }
+
+    /// Last day of the week containing this *Date*, for weeks starting on *week_start*
+    pub fn end_of_week(&self, week_start: Weekday) -> Result<Date, String> {
+        self.start_of_week(week_start)?.add_days(6)
+    }
+
+    /// The first Monday to Friday after this *Date*, so a Friday goes to the next Monday
+    pub fn next_business_day(&self) -> Result<Date, String> {
+        self.business_day_step(1, None)
+    }
+
+    /// The last Monday to Friday before this *Date* that is not one of the *holidays*,
+    /// so a Monday goes to the previous Friday. Fails if a whole year goes by without one
+    pub fn previous_business_day(&self, holidays: Option<&dyn HolidayProvider>) -> Result<Date, String> {
+        self.business_day_step(-1, holidays)
+    }
+
+    /// The date *n* business days after this *Date*, or before it for a negative *n*,
+    /// skipping weekends and *holidays*. Counting starts from the next business day, so
+    /// one business day after a Saturday is Monday; *n* = 0 gives this *Date* back as is.
+    /// Fails if a whole year goes by without a business day
+    pub fn add_business_days(&self, n: i32, holidays: Option<&dyn HolidayProvider>) -> Result<Date, String> {
+        let mut date = self.clone();
+        date.is_valid()?;
+        for _ in 0..n.unsigned_abs() {
+            date = date.business_day_step(n.signum() as i64, holidays)?;
+        }
+        Ok(date)
+    }
+
+    /// The first business day in the direction of *step* (1 or -1), looking at most a year
+    /// away, so *holidays* that never end give an error rather than a scan of every date
+    fn business_day_step(&self, step: i64, holidays: Option<&dyn HolidayProvider>) -> Result<Date, String> {
+        let mut date = self.clone();
+        for _ in 0..366 {
+            date = date.add_days_i64(step)?;
+            let weekend = matches!(date.weekday()?, Weekday::Saturday | Weekday::Sunday);
+            if !weekend && !holidays.is_some_and(|holidays| holidays.is_holiday(&date)) {
+                return Ok(date);
+            }
+        }
+        Err(format!("{}: No business day within a year", self))
+    }
+
+    /// The *n*th (from 1) *weekday* of a month, as in "third Monday of January"
+    pub fn nth_weekday_of_month(year: i32, month: u8, weekday: Weekday, n: u8) -> Result<Date, String> {
+        let first = Date::new(year, month, 1);
+        let offset = weekday.days_from_monday() - first.weekday()?.days_from_monday();
+        let date = first.add_days(offset.rem_euclid(7) + 7 * (n as i32 - 1))?;
+        if n == 0 || date.month != month || date.year != year {
+            return Err(format!("There is no {:?} number {} in {} {}", weekday, n,
+                               first.month_name().unwrap_or_default(), year));
+        }
+        Ok(date)
+    }
+
+    /// Last day of this *Date*'s month
+    pub fn end_of_month(&self) -> Result<Date, String> {
+        self.is_valid()?;
+        Ok(Date::new(self.year, self.month, Date::last_day(self.year, self.month)))
+    }
+
+    /// The last *weekday* of a month, as in "last Friday of the month"
+    pub fn last_weekday_of_month(year: i32, month: u8, weekday: Weekday) -> Result<Date, String> {
+        let last = Date::new(year, month, 1).end_of_month()?;
+        let offset = last.weekday()?.days_from_monday() - weekday.days_from_monday();
+        last.add_days(-offset.rem_euclid(7))
+    }
+
+    /// Quarter of the year (1 to 4), or *None* if the month is out of range
+    pub fn quarter(&self) -> Option<u8> {
+        if (1..=12).contains(&self.month) { Some((self.month - 1) / 3 + 1) } else { None }
+    }
+
+    /// Each (year, quarter) holding some date from *start* (included) to *end* (excluded), in order.
+    /// Empty if *start* has no quarter
+    pub fn quarters_in(start: Date, end: Date) -> impl Iterator<Item = (i32, u8)> {
+        let first = start.quarter().map(|quarter| (start.year, quarter));
+        std::iter::successors(first, |&(year, quarter)| {
+            if quarter < 4 {
+                Some((year, quarter + 1))
+            } else if year == i32::MAX {
+                None
+            } else {
+                Some((Date::next_year(year), 1))
+            }
+        })
+        .take_while(move |&(year, quarter)| {
+            start < end && Date::new(year, (quarter - 1) * 3 + 1, 1) < end
+        })
+    }
+
+    /// Half of the year: 1 from January to June, 2 from July to December
+    pub fn semester(&self) -> u8 {
+        if self.month <= 6 { 1 } else { 2 }
+    }
+
+    /// First day of this *Date*'s semester: January 1 or July 1
+    pub fn first_day_of_semester(&self) -> Date {
+        Date::new(self.year, if self.semester() == 1 { 1 } else { 7 }, 1)
+    }
+
+    /// Last day of this *Date*'s semester: June 30 or December 31
+    pub fn last_day_of_semester(&self) -> Date {
+        if self.semester() == 1 {
+            Date::new(self.year, 6, 30)
+        } else {
+            Date::new(self.year, 12, 31)
+        }
+    }
+
+    /// First and last day of the *unit* period this *Date* falls in
+    fn period_bounds(&self, unit: Period, week_start: Weekday) -> Result<(Date, Date), String> {
+        self.is_valid()?;
+        Ok(match unit {
+            Period::Day => (self.clone(), self.clone()),
+            Period::Week => {
+                let start = self.start_of_week(week_start)?;
+                let end = start.add_days(6)?;
+                (start, end)
+            }
+            Period::Month => (Date::new(self.year, self.month, 1), self.end_of_month()?),
+            Period::Quarter => {
+                let first_month = (self.quarter().ok_or(DateError::InvalidMonth)? - 1) * 3 + 1;
+                (Date::new(self.year, first_month, 1),
+                 Date::new(self.year, first_month + 2, 1).end_of_month()?)
+            }
+            Period::Year => (Date::new(self.year, 1, 1), Date::new(self.year, 12, 31)),
+        })
+    }
+
+    /// Start of the *unit* period this *Date* falls in, for weeks starting on *week_start*
+    pub fn round_down(&self, unit: Period, week_start: Weekday) -> Result<Date, String> {
+        Ok(self.period_bounds(unit, week_start)?.0)
+    }
+
+    /// Start of the next *unit* period, unless this *Date* already starts one.
+    /// Weeks start on *week_start*
+    pub fn round_up(&self, unit: Period, week_start: Weekday) -> Result<Date, String> {
+        let (start, end) = self.period_bounds(unit, week_start)?;
+        if *self == start {
+            return Ok(start);
+        }
+        end.succ().ok_or_else(|| format!("{}: Rounding up out of range", self))
+    }
+
+    /// Start of the *unit* period nearest to this *Date*: the one it falls in or the next one,
+    /// going up on ties. Weeks start on Monday, as in *Period::Week*
+    pub fn round_to(&self, unit: Period) -> Result<Date, String> {
+        let down = self.round_down(unit, Weekday::Monday)?;
+        let up = self.round_up(unit, Weekday::Monday)?;
+        if self.to_rata_die() - down.to_rata_die() < up.to_rata_die() - self.to_rata_die() {
+            Ok(down)
+        } else {
+            Ok(up)
+        }
+    }
+
+    /// This *Date*, or October 15, 1582 if it is one of the days lost in the Gregorian reform
+    pub fn snap_out_of_gap(&self) -> Date {
+        if Calendar::ROME.skips(self) {
+            Date::gregorian_reform_date()
+        } else {
+            self.clone()
+        }
+    }
+
+    /// The Gregorian reform that happened in this *Date*'s year, or *None* for years without one
+    pub fn reform_info(&self) -> Option<ReformInfo> {
+        let Calendar { julian_end, gregorian_start } = Calendar::ROME;
+        if self.year != julian_end.year {
+            return None;
+        }
+        Some(ReformInfo {
+            first_skipped: Date::new(julian_end.year, julian_end.month, julian_end.day + 1),
+            last_skipped: Date::new(gregorian_start.year, gregorian_start.month, gregorian_start.day - 1),
+            skipped_days: gregorian_start.day - julian_end.day - 1,
+            last_julian: julian_end,
+            first_gregorian: gregorian_start,
+        })
+    }
+
+    /// ISO 8601 week-year, the first half of *iso_week*, which differs from the calendar
+    /// year for some days around New Year
+    pub fn iso_week_year(&self) -> Result<i32, String> {
+        Ok(self.iso_week()?.0)
+    }
+
+    /// ISO 8601 week as (week-year, week number): weeks start on Monday and week 1 is the
+    /// one holding the year's first Thursday, so early January or late December days may
+    /// belong to the adjacent week-year
+    pub fn iso_week(&self) -> Result<(i32, u8), String> {
+        self.is_valid()?;
+        let ordinal = self.day_of_year();
+        let weekday = (Date::jan1_weekday(self.year).days_from_monday() + ordinal - 1).rem_euclid(7);
+        let mut year = self.year;
+        let mut thursday = ordinal + Weekday::Thursday.days_from_monday() - weekday;
+        if thursday < 1 {
+            if year == i32::MIN {
+                return Err(format!("{}: ISO week out of range", self));
+            }
+            year = Date::previous_year(year);
+            thursday += Date::year_days(year);
+        } else if thursday > Date::year_days(year) {
+            if year == i32::MAX {
+                return Err(format!("{}: ISO week out of range", self));
+            }
+            thursday -= Date::year_days(year);
+            year = Date::next_year(year);
+        }
+        Ok((year, ((thursday - 1) / 7 + 1) as u8))
+    }
+
+    /// How many times each weekday, from Monday to Sunday, occurs in *year*
+    pub fn weekday_histogram(year: i32) -> Result<[u32; 7], String> {
+        Date::new(year, 1, 1).is_valid()?;
+        let days = Date::year_days(year);
+        let mut histogram = [(days / 7) as u32; 7];
+        let first = Date::jan1_weekday(year).days_from_monday();
+        for weekday in first..first + days % 7 {
+            histogram[weekday as usize % 7] += 1;
+        }
+        Ok(histogram)
+    }
+
+    /// Simple week number: week 1 is the one holding January 1st, however short,
+    /// and new weeks begin on *week_start*
+    pub fn week_of_year(&self, week_start: Weekday) -> Result<u8, String> {
+        self.is_valid()?;
+        let offset = Date::simple_week_offset(self.year, week_start);
+        Ok(((self.day_of_year() - 1 + offset) / 7 + 1) as u8)
+    }
+
+    /// Inverse of *week_of_year*: the *weekday* of the given simple *week* of *year*
+    pub fn from_simple_week(year: i32, week: u8, weekday: Weekday, week_start: Weekday) -> Result<Date, String> {
+        Date::new(year, 1, 1).is_valid()?;
+        let offset = Date::simple_week_offset(year, week_start);
+        let day_of_week = (weekday.days_from_monday() - week_start.days_from_monday()).rem_euclid(7);
+        let ordinal = (week as i32 - 1) * 7 + day_of_week - offset + 1;
+        if week == 0 || ordinal < 1 || ordinal > Date::year_days(year) {
+            return Err(format!("There is no {} in week {} of {}", weekday.name(), week, year));
+        }
+        Ok(Date::from_day_of_year(year, ordinal))
+    }
+
+    /// How many days of the first simple week of *year* fall in the previous year
+    fn simple_week_offset(year: i32, week_start: Weekday) -> i32 {
+        (Date::jan1_weekday(year).days_from_monday() - week_start.days_from_monday()).rem_euclid(7)
+    }
+
+    /// Day of the week of January 1st of *year*, in constant time from its Rata Die number.
+    /// Counted from the Unix epoch like *weekday*. *year* must not be 0
+    fn jan1_weekday(year: i32) -> Weekday {
+        Date::new(year, 1, 1).weekday_unchecked()
+    }
+
+    /// Canonical label of the period this *Date* falls in, such as "2021-07-22", "2021-W29",
+    /// "2021-07", "2021-Q3" or "2021". Labels sort chronologically within each kind of period
+    pub fn period_key(&self, period: Period) -> Result<String, String> {
+        self.is_valid()?;
+        let year = Date::year_label(self.year);
+        Ok(match period {
+            Period::Day => format!("{}-{:02}-{:02}", year, self.month, self.day),
+            Period::Week => {
+                let (week_year, week) = self.iso_week()?;
+                format!("{}-W{:02}", Date::year_label(week_year), week)
+            }
+            Period::Month => format!("{}-{:02}", year, self.month),
+            Period::Quarter => format!("{}-Q{}", year, self.quarter().ok_or(DateError::InvalidMonth)?),
+            Period::Year => year,
+        })
+    }
+
+    /// Year with at least 4 digits; BC years get a leading '-'
+    fn year_label(year: i32) -> String {
+        let sign = if year < 0 { "-" } else { "" };
+        format!("{}{:04}", sign, year.unsigned_abs())
+    }
+
+    /// Fiscal year the *Date* belongs to, for a fiscal year starting on the 1st of *start_month*.
+    /// Fiscal years are named after the calendar year they end in, so with a July start
+    /// July 2021 belongs to fiscal year 2022
+    pub fn fiscal_year(&self, start_month: u8) -> Result<i32, String> {
+        self.is_valid()?;
+        Date::check_start_month(start_month)?;
+        if start_month == 1 || self.month < start_month {
+            return Ok(self.year);
+        }
+        match self.year {
+            -1 => Ok(1),
+            year => year.checked_add(1).ok_or_else(|| format!("{}: Fiscal year out of range", self)),
+        }
+    }
+
+    /// Quarter (1 to 4) of the fiscal year the *Date* belongs to
+    pub fn fiscal_quarter(&self, start_month: u8) -> Result<u8, String> {
+        self.is_valid()?;
+        Date::check_start_month(start_month)?;
+        Ok((self.month + 12 - start_month) % 12 / 3 + 1)
+    }
+
+    fn check_start_month(start_month: u8) -> Result<(), String> {
+        if (1..=12).contains(&start_month) {
+            Ok(())
+        } else {
+            Err(format!("{}: Invalid fiscal year start month", start_month))
+        }
+    }
+
+    /// How many days there are between two given *Date*s
+    pub fn days_between_dates(first: &Date, last: &Date) -> Result<i32, String> {
+        first.is_valid()?;
+        last.is_valid()?;
+
+        let days = last.to_rata_die() - first.to_rata_die();
+        i32::try_from(days).map_err(|_| format!("{} to {}: Day count out of range", first, last))
+    }
+
+    /// How many days apart two given *Date*s are, whichever comes first. Never negative,
+    /// and wide enough for any two dates, even *Date::MIN* and *Date::MAX*
+    pub fn days_apart(first: &Date, last: &Date) -> Result<i64, String> {
+        first.is_valid()?;
+        last.is_valid()?;
+        Ok((last.to_rata_die() - first.to_rata_die()).abs())
+    }
+
+    /// The candidate fewest days away from this *Date*, the earlier one on ties.
+    /// Invalid candidates are ignored; *None* if there is no valid one or this *Date* is invalid
+    pub fn closest<'a>(&self, candidates: &'a [Date]) -> Option<&'a Date> {
+        candidates.iter()
+            .filter_map(|candidate| Some((Date::days_apart(self, candidate).ok()?, candidate)))
+            .min()
+            .map(|(_, candidate)| candidate)
+    }
+
+    /// The date halfway between two given *Date*s, rounding toward *first* on odd spans
+    pub fn midpoint(first: &Date, last: &Date) -> Result<Date, String> {
+        first.is_valid()?;
+        last.is_valid()?;
+        let span = last.to_serial() - first.to_serial();
+        Date::from_serial(first.to_serial() + span / 2)
+    }
+
+    /// Days someone born on *birth* has lived *on* a given date.
+    /// Unlike *days_between_dates* it fails if *on* comes before *birth*, a likely typo
+    pub fn age_in_days(birth: &Date, on: &Date) -> Result<i32, String> {
+        let days = Date::days_between_dates(birth, on)?;
+        if days < 0 {
+            return Err(format!("{} is before the birth date {}", on, birth));
+        }
+        Ok(days)
+    }
+
+    /// Whole weeks and leftover days between two given *Date*s.
+    /// Weeks are rounded down so the leftover is always 0 to 6 and *weeks * 7 + days*
+    /// gives back *days_between_dates*: a span of -10 days is (-2, 4)
+    pub fn weeks_between(first: &Date, last: &Date) -> Result<(i32, u8), String> {
+        let days = Date::days_between_dates(first, last)?;
+        Ok((days.div_euclid(7), days.rem_euclid(7) as u8))
+    }
+
+    /// Like *Display*, with the year zero-padded to at least 4 digits so dates line up
+    /// in tables: "February 3, 0005 BC"
+    pub fn display_padded(&self) -> String {
+        let era = if self.year < 0 { " BC" } else { "" };
+        format!("{} {}, {:04}{}", self.month_name().unwrap_or("<invalid month>"),
+                self.day, self.year.unsigned_abs(), era)
+    }
+
+    /// ISO 8601 ordinal date *YYYY-DDD*, as in "2021-203"; BC years get a leading '-'
+    pub fn ordinal_iso(&self) -> Result<String, String> {
+        self.is_valid()?;
+        Ok(format!("{}-{:03}", Date::year_label(self.year), self.day_of_year()))
+    }
+
+    /// *YYYY-MM-DD* with expanded years: a '+' and more digits past 9999, as in "+12021-07-22".
+    /// BC years get a leading '-' as in *compact* and *parse*, which reads every output back.
+    /// That is not ISO 8601, whose years are astronomical: 44 BC is "-0044" here but "-0043"
+    /// there (see *proleptic_year*)
+    pub fn expanded_date(&self) -> String {
+        let year = if self.year > 9999 {
+            format!("+{}", self.year)
+        } else {
+            Date::year_label(self.year)
+        };
+        format!("{}-{:02}-{:02}", year, self.month, self.day)
+    }
+
+    /// RFC 3339 *full-date*, as in "2021-07-22". Only years 1 to 9999 can be written that way
+    pub fn rfc3339_date(&self) -> Result<String, String> {
+        self.is_valid()?;
+        if !(1..=9999).contains(&self.year) {
+            return Err(format!("{}: RFC 3339 only allows years 1 to 9999", self));
+        }
+        Ok(format!("{:04}-{:02}-{:02}", self.year, self.month, self.day))
+    }
+
+    /// Long English form, such as "Thursday the 22nd of February, 2001 AD"
+    pub fn long_form(&self) -> Result<String, String> {
+        let weekday = self.weekday()?;
+        let suffix = match (self.day % 10, self.day % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        let era = match self.era() {
+            Era::Bc => "BC",
+            Era::Ad => "AD",
+        };
+        Ok(format!("{} the {}{} of {}, {} {}", weekday.name(), self.day, suffix,
+                   self.month_name().unwrap_or_default(), self.year.unsigned_abs(), era))
+    }
+
+    /// Span from *first* to *last* in *unit*s, negative if *last* comes first.
+    /// Only whole weeks, months and years are counted, so the magnitude is rounded down:
+    /// January 31 to February 28 is 0 months
+    pub fn duration_in(first: &Date, last: &Date, unit: DurationUnit) -> Result<i64, String> {
+        let days = Date::days_between_dates(first, last)? as i64;
+        if days < 0 {
+            return Ok(-Date::duration_in(last, first, unit)?);
+        }
+        let mut months = last.month_index() - first.month_index();
+        if last.day < first.day {
+            months -= 1;
+        }
+        Ok(match unit {
+            DurationUnit::Days => days,
+            DurationUnit::Weeks => days / 7,
+            DurationUnit::Months => months,
+            DurationUnit::Years => months / 12,
+        })
+    }
+
+    /// The signed difference from *other* to this *Date*, in total days and broken down into
+    /// whole years and months plus leftover days, all with the same sign.
+    /// Months count as in *duration_in*: January 31 to March 1 is 1 month and 1 day
+    pub fn checked_sub(&self, other: &Date) -> Result<DateDelta, String> {
+        let total_days = Date::days_between_dates(other, self)? as i64;
+        if total_days < 0 {
+            let delta = other.checked_sub(self)?;
+            return Ok(DateDelta {
+                total_days: -delta.total_days,
+                years: -delta.years,
+                months: -delta.months,
+                days: -delta.days,
+            });
+        }
+        let months = Date::duration_in(other, self, DurationUnit::Months)?;
+        let (year, month) = Date::from_month_index(other.month_index() + months)
+            .ok_or_else(|| format!("{} - {}: Out of range", self, other))?;
+        let last_day = Date::new(year, month, 1).end_of_month()?.day;
+        let anchor = Date::new(year, month, other.day.min(last_day)).snap_out_of_gap();
+        Ok(DateDelta {
+            total_days,
+            years: (months / 12) as i32,
+            months: (months % 12) as i32,
+            days: Date::days_between_dates(&anchor, self)?,
+        })
+    }
+
+    /// This *Date* in *year*. A day the month lacks there, as February 29 in a common year,
+    /// is clamped to the month's last day, and one lost in the Gregorian reform goes to
+    /// October 15, 1582
+    pub fn with_year(&self, year: i32) -> Result<Date, String> {
+        self.with_year_month(year, self.month)
+    }
+
+    /// This *Date* in *month*, clamping the day as *with_year* does, so January 31
+    /// becomes February 28 or 29
+    pub fn with_month(&self, month: u8) -> Result<Date, String> {
+        self.with_year_month(self.year, month)
+    }
+
+    /// This *Date* on *day* of its month, which must exist
+    pub fn with_day(&self, day: u8) -> Result<Date, String> {
+        self.is_valid()?;
+        let date = Date::new(self.year, self.month, day);
+        date.is_valid()?;
+        Ok(date)
+    }
+
+    fn with_year_month(&self, year: i32, month: u8) -> Result<Date, String> {
+        self.is_valid()?;
+        Date::validate(year, month, 1)?;
+        Ok(Date::new(year, month, self.day.min(Date::last_day(year, month))).snap_out_of_gap())
+    }
+
+    /// The same day of the next month, with *policy* for days the next month lacks.
+    /// Days lost in the Gregorian reform go to October 15, 1582 unless *policy* is *Skip*
+    pub fn next_month_same_day(&self, policy: DayPolicy) -> Result<Date, String> {
+        self.is_valid()?;
+        let (year, month) = Date::from_month_index(self.month_index() + 1)
+            .ok_or_else(|| format!("{}: Next month out of range", self))?;
+        let last = Date::new(year, month, 1).end_of_month()?;
+        let date = match policy {
+            _ if self.day <= last.day => Date::new(year, month, self.day),
+            DayPolicy::Clamp => last,
+            DayPolicy::Skip => return Err(format!("{} {} has no day {}",
+                                                  last.month_name().unwrap_or_default(), year, self.day)),
+            DayPolicy::Overflow => last.add_days((self.day - last.day) as i32)?,
+        };
+        match date.check() {
+            Ok(()) => Ok(date),
+            Err(DateError::SkippedByReform { .. }) if policy != DayPolicy::Skip => Ok(date.snap_out_of_gap()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Months elapsed from January, 1 BC (astronomical year 0) to this *Date*'s month
+    fn month_index(&self) -> i64 {
+        self.proleptic_year() as i64 * 12 + self.month as i64 - 1
+    }
+
+    /// Inverse of *month_index*: (year, month), or *None* if the year does not fit in an *i32*
+    fn from_month_index(index: i64) -> Option<(i32, u8)> {
+        let astronomical = index.div_euclid(12);
+        let year = if astronomical <= 0 { astronomical - 1 } else { astronomical };
+        Some((i32::try_from(year).ok()?, (index.rem_euclid(12) + 1) as u8))
+    }
+
+    /// Parse a compact *YYYYMMDD* date, as found in logs and file names.
+    /// A leading '-' marks a BC year
+    pub fn parse_compact(s: &str) -> Result<Date, ParseError> {
+        Date::trimmed(s, Date::compact_date)
+    }
+
+    fn compact_date(s: &str) -> Result<Date, ParseError> {
+        let offset = if s.starts_with('-') { 1 } else { 0 };
+        let digits = &s[offset..];
+        let message = || format!("{}: Invalid compact date, expected YYYYMMDD", s);
+        Date::numeric_at(s, offset, digits, 8, 8, message)?;
+        let date = Date::new(
+            if offset == 1 { -1 } else { 1 } * Date::digits_value(&digits[..4]) as i32,
+            Date::digits_value(&digits[4..6]) as u8,
+            Date::digits_value(&digits[6..]) as u8,
+        );
+        Date::validated(s, date, [offset, offset + 4, offset + 6])
+    }
+
+    /// Format as a compact *YYYYMMDD* string, as in "20210722"; BC years get a leading '-'.
+    /// Only years 1 to 9999 sort as text in date order, since BC ones sort backwards, and
+    /// years past 9999 take more than 8 digits, so *parse_compact* does not read them back
+    pub fn compact(&self) -> String {
+        format!("{}{:02}{:02}", Date::year_label(self.year), self.month, self.day)
+    }
+
+    /// Parse a US-style *MM/DD/YY* date, as found in legacy data.
+    /// Two-digit years are ambiguous, so the caller chooses the century with *pivot* (0 to 100):
+    /// years below it fall in the 2000s and the rest in the 1900s.
+    /// With pivot 69, 00-68 become 2000-2068 and 69-99 become 1969-1999
+    pub fn parse_with_pivot(s: &str, pivot: u8) -> Result<Date, ParseError> {
+        Date::trimmed(s, |s| Date::slash_date(s, pivot))
+    }
+
+    fn slash_date(s: &str, pivot: u8) -> Result<Date, ParseError> {
+        if pivot > 100 {
+            return Err(ParseError::at(s, 0, format!("{}: Invalid pivot, expected 0 to 100", pivot)));
+        }
+        let message = || format!("{}: Invalid date, expected MM/DD/YY", s);
+        let ([month, day, year], [month_at, day_at, year_at]) =
+            Date::delimited_fields(s, 0, '/', [(1, 2), (1, 2), (2, 2)], message)?;
+        let century = if year < pivot as u32 { 2000 } else { 1900 };
+        let date = Date::new(century + year as i32, month as u8, day as u8);
+        Date::validated(s, date, [year_at, month_at, day_at])
+    }
+
+    /// Parse a date in any of the supported formats, picked from the shape of *s*:
+    /// - ISO 8601 *YYYY-MM-DD*, as in "2021-07-22", "-0044-03-15" or "+12021-07-22"
+    /// - ISO 8601 ordinal *YYYY-DDD*, as in "2021-203" (see *ordinal_iso*)
+    /// - compact *YYYYMMDD*, as in "20210722"
+    /// - US-style *MM/DD/YY*, with two-digit years pivoting at 69 (see *parse_with_pivot*)
+    /// - prose as printed by *Display*, as in "July 22, 2021" or "March 15, 44 BC",
+    ///   or day first, as in "15 March 44 BC"
+    pub fn parse(s: &str) -> Result<Date, ParseError> {
+        Date::trimmed(s, Date::any_date)
+    }
+
+    /// Every valid ISO 8601 *YYYY-MM-DD* date found in *text*, in order. Dates must stand
+    /// on their own, not inside longer numbers, and invalid ones such as "2021-02-30" are
+    /// skipped. Years must have 4 digits, so BC dates are not recognised
+    pub fn parse_all(text: &str) -> Vec<Date> {
+        let bytes = text.as_bytes();
+        let is_digit = |index: usize| bytes.get(index).is_some_and(u8::is_ascii_digit);
+        (0..bytes.len().saturating_sub(9))
+            .filter(|&start| {
+                (start == 0 || !is_digit(start - 1)) && !is_digit(start + 10)
+                    && (0..10).all(|offset| match offset {
+                        4 | 7 => bytes[start + offset] == b'-',
+                        _ => is_digit(start + offset),
+                    })
+            })
+            .filter_map(|start| Date::parse_iso(&text[start..start + 10]).ok())
+            .collect()
+    }
+
+    fn any_date(s: &str) -> Result<Date, ParseError> {
+        let unsigned = s.strip_prefix('-').unwrap_or(s);
+        let is_month = |word: &str| MONTHS.iter().any(|month| month.eq_ignore_ascii_case(word));
+        let mut words = s.split(' ');
+        if s.contains('/') {
+            Date::slash_date(s, 69)
+        } else if words.next().is_some_and(is_month) || words.next().is_some_and(is_month) {
+            Date::parse_prose(s)
+        } else if !unsigned.is_empty() && unsigned.bytes().all(|b| b.is_ascii_digit()) {
+            Date::compact_date(s)
+        } else if unsigned.contains('-') {
+            Date::parse_iso(s)
+        } else {
+            Err(ParseError::at(s, 0, format!("{}: Unrecognised date, expected YYYY-MM-DD, YYYYMMDD, \
+                                              MM/DD/YY or Month D, YYYY", s)))
+        }
+    }
+
+    /// Parse an ISO 8601 *YYYY-MM-DD* date, or an ordinal *YYYY-DDD* one; a leading '-'
+    /// marks a BC year, and a leading '+' an expanded one, as *expanded_date* writes them.
+    /// A time part, as in "2021-07-22T13:00:00Z", or a bare 'Z' is ignored
+    fn parse_iso(s: &str) -> Result<Date, ParseError> {
+        let s = s.split('T').next().unwrap_or_default();
+        let s = s.strip_suffix('Z').unwrap_or(s);
+        let offset = if s.starts_with(['-', '+']) { 1 } else { 0 };
+        if let Some((year, ordinal)) = s[offset..].split_once('-').filter(|(_, ordinal)| ordinal.len() == 3) {
+            return Date::parse_ordinal(s, offset, year, ordinal);
+        }
+        let message = || format!("{}: Invalid date, expected YYYY-MM-DD", s);
+        let ([year, month, day], positions) =
+            Date::delimited_fields(s, offset, '-', [(4, 10), (2, 2), (2, 2)], message)?;
+        let year = Date::iso_year(s, offset, year)?;
+        Date::validated(s, Date::new(year, month as u8, day as u8), positions)
+    }
+
+    /// The year *value* of an ISO date *s*, negative if *s* starts with '-'.
+    /// Fails at byte *offset* if it does not fit in an *i32*
+    fn iso_year(s: &str, offset: usize, value: u32) -> Result<i32, ParseError> {
+        let year = if s.starts_with('-') { -(value as i64) } else { value as i64 };
+        i32::try_from(year).map_err(|_| ParseError::at(s, offset, format!("{}: Year out of range", s)))
+    }
+
+    /// Parse the *year* and *ordinal* fields of an ordinal ISO date *s*, the year starting at byte *offset*
+    fn parse_ordinal(s: &str, offset: usize, year: &str, ordinal: &str) -> Result<Date, ParseError> {
+        let message = || format!("{}: Invalid date, expected YYYY-DDD", s);
+        let ordinal_at = offset + year.len() + 1;
+        let year = Date::numeric_at(s, offset, year, 4, 10, message)?;
+        let ordinal = Date::numeric_at(s, ordinal_at, ordinal, 3, 3, message)? as u16;
+        let year = Date::iso_year(s, offset, year)?;
+        Date::from_ordinal(year, ordinal)
+            .map_err(|error| ParseError::at(s, if year == 0 { offset } else { ordinal_at }, error))
+    }
+
+    /// Parse a date written as *Display* prints it, such as "July 22, 2021" or "March 15, 44 BC"
+    /// Day-first prose, as in "15 March 44 BC", is accepted too. Either way BC years map to
+    /// the same negative years as in ISO dates, so "March 15, 44 BC" is "-0044-03-15"
+    fn parse_prose(s: &str) -> Result<Date, ParseError> {
+        let message = || format!("{}: Invalid date, expected Month D, YYYY or D Month YYYY", s);
+        let month_index = |name: &str, at: usize| {
+            MONTHS.iter().position(|month| month.eq_ignore_ascii_case(name))
+                .ok_or_else(|| ParseError::at(s, at, message()))
+        };
+        let (month, day, [month_at, day_at, year_at]) = if s.starts_with(|c: char| c.is_ascii_digit()) {
+            let day_len = s.bytes().take_while(|b| b.is_ascii_digit()).count();
+            let day = Date::numeric_at(s, 0, &s[..day_len], 1, 2, message)?;
+            if !s[day_len..].starts_with(' ') {
+                return Err(ParseError::at(s, day_len, message()));
+            }
+            let month_at = day_len + 1;
+            let name = s[month_at..].split(' ').next().unwrap_or_default();
+            let month = month_index(name, month_at)?;
+            let year_at = month_at + name.len() + 1;
+            if !s[month_at + name.len()..].starts_with(' ') {
+                return Err(ParseError::at(s, month_at + name.len(), message()));
+            }
+            (month, day, [month_at, 0, year_at])
+        } else {
+            let name = s.split(' ').next().unwrap_or_default();
+            let month = month_index(name, 0)?;
+            let day_at = (name.len() + 1).min(s.len());
+            let day_len = s[day_at..].bytes().take_while(|b| b.is_ascii_digit()).count();
+            let day = Date::numeric_at(s, day_at, &s[day_at..day_at + day_len], 1, 2, message)?;
+            if s[day_at + day_len..].get(..2) != Some(", ") {
+                return Err(ParseError::at(s, day_at + day_len, message()));
+            }
+            (month, day, [0, day_at, day_at + day_len + 2])
+        };
+        let (sign, year) = match s[year_at..].strip_suffix(" BC") {
+            Some(year) => (-1, year),
+            None => (1, &s[year_at..]),
+        };
+        let year = Date::numeric_at(s, year_at, year, 1, 9, message)?;
+        let date = Date::new(sign * year as i32, month as u8 + 1, day as u8);
+        Date::validated(s, date, [year_at, month_at, day_at])
+    }
+
+    /// Split the three numeric fields of *s*, starting at byte *offset*, at *separator*.
+    /// Each one must have between the minimum and maximum digits in *lengths*.
+    /// Returns the fields and where they start
+    fn delimited_fields<F: Fn() -> String>(s: &str, offset: usize, separator: char,
+                                           lengths: [(usize, usize); 3], message: F)
+                                           -> Result<([u32; 3], [usize; 3]), ParseError> {
+        let mut values = [0; 3];
+        let mut positions = [0; 3];
+        let mut start = offset;
+        for (index, &(min_len, max_len)) in lengths.iter().enumerate() {
+            let end = if index < 2 {
+                s[start..].find(separator).map_or(s.len(), |found| start + found)
+            } else {
+                s.len()
+            };
+            values[index] = Date::numeric_at(s, start, &s[start..end], min_len, max_len, &message)?;
+            positions[index] = start;
+            if index < 2 && end == s.len() {
+                return Err(ParseError::at(s, end, message()));
+            }
+            start = end + separator.len_utf8();
+        }
+        Ok((values, positions))
+    }
+
+    /// Numeric value of *field*, found at byte *start* of *s*, if it has between *min_len*
+    /// and *max_len* ASCII digits; otherwise an error at the first unexpected character
+    fn numeric_at<F: Fn() -> String>(s: &str, start: usize, field: &str, min_len: usize,
+                                     max_len: usize, message: F) -> Result<u32, ParseError> {
+        let digits = field.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if digits > max_len {
+            Err(ParseError::at(s, start + max_len, message()))
+        } else if digits < field.len() || digits < min_len {
+            Err(ParseError::at(s, start + digits, message()))
+        } else {
+            Ok(Date::digits_value(field))
+        }
+    }
+
+    /// Run *parse* on *s* without its surrounding whitespace, so every parser accepts
+    /// padded input; error positions still count from the start of *s*
+    fn trimmed<F: Fn(&str) -> Result<Date, ParseError>>(s: &str, parse: F) -> Result<Date, ParseError> {
+        let padding = s.chars().take_while(|c| c.is_whitespace()).count();
+        parse(s.trim()).map_err(|error| ParseError { position: error.position + padding, ..error })
+    }
+
+    /// *date* if it is valid, or an error pointing at its year, month or day,
+    /// which start at the given byte *positions* of *s*
+    fn validated(s: &str, date: Date, positions: [usize; 3]) -> Result<Date, ParseError> {
+        match date.check() {
+            Ok(()) => Ok(date),
+            Err(error) => {
+                let position = match error {
+                    DateError::YearZero => positions[0],
+                    DateError::InvalidMonth => positions[1],
+                    DateError::SkippedByReform { .. } | DateError::InvalidDay(_) => positions[2],
+                };
+                Err(ParseError::at(s, position, error.to_string()))
+            }
+        }
+    }
+
+    /// Format with a pattern of *strftime*-like specifiers:
+    /// - *%Y*: year with at least 4 digits, '-' for BC, as in "2021" or "-0044"
+    /// - *%m*, *%d*: two-digit month and day
+    /// - *%B*, *%b*: month name and its three-letter abbreviation, as in "July" and "Jul"
+    /// - *%A*, *%a*: weekday name and its abbreviation, as in "Thursday" and "Thu"
+    /// - *%%*: a literal '%'
+    pub fn format(&self, pattern: &str) -> Result<String, String> {
+        self.is_valid()?;
+        let month = self.month_name().unwrap_or_default();
+        let mut formatted = String::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                formatted.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => formatted += &Date::year_label(self.year),
+                Some('m') => formatted += &format!("{:02}", self.month),
+                Some('d') => formatted += &format!("{:02}", self.day),
+                Some('B') => formatted += month,
+                Some('b') => formatted += &month[..3],
+                Some('A') => formatted += self.weekday_name()?,
+                Some('a') => formatted += self.weekday_short_name()?,
+                Some('%') => formatted.push('%'),
+                _ => return Err(format!("{}: Invalid format pattern", pattern)),
+            }
+        }
+        Ok(formatted)
+    }
+
+    /// Parse a date with the specifiers of *format*, so *parse_exact(&date.format(p)?, p)*
+    /// gives back *date*. The pattern must have a year, a month and a day; a weekday, if any,
+    /// must match the date. *%Y* takes every digit available but those needed by the *%m*
+    /// and *%d* right after it, as in "%Y%m%d"
+    pub fn parse_exact(s: &str, pattern: &str) -> Result<Date, ParseError> {
+        Date::trimmed(s, |s| Date::exact_date(s, pattern))
+    }
+
+    fn exact_date(s: &str, pattern: &str) -> Result<Date, ParseError> {
+        let (mut year, mut month, mut day, mut weekday) = (None, None, None, None);
+        let mut positions = [0; 4];
+        let mut rest = s;
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let position = s.len() - rest.len();
+            let mismatch = || ParseError::at(s, position,
+                                             format!("{}: Does not match the pattern {}", s, pattern));
+            if c != '%' {
+                rest = rest.strip_prefix(c).ok_or_else(mismatch)?;
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => {
+                    let (sign, unsigned) = match rest.strip_prefix('-') {
+                        Some(unsigned) => (-1, unsigned),
+                        None => (1, rest),
+                    };
+                    let mut reserved = 0;
+                    let mut ahead = chars.clone();
+                    while ahead.next() == Some('%') && matches!(ahead.next(), Some('m') | Some('d')) {
+                        reserved += 2;
+                    }
+                    let len = unsigned.bytes().take_while(|b| b.is_ascii_digit()).count()
+                        .saturating_sub(reserved);
+                    let digits = Date::numeric_field(&unsigned[..len], 4, 9).ok_or_else(mismatch)?;
+                    year = Some(sign * digits as i32);
+                    positions[0] = position;
+                    rest = &unsigned[len..];
+                }
+                Some('m') => {
+                    month = Some(Date::take_digits(&mut rest, 2).ok_or_else(mismatch)? as u8);
+                    positions[1] = position;
+                }
+                Some('d') => {
+                    day = Some(Date::take_digits(&mut rest, 2).ok_or_else(mismatch)? as u8);
+                    positions[2] = position;
+                }
+                Some(spec @ 'B') | Some(spec @ 'b') => {
+                    let len = if spec == 'B' { None } else { Some(3) };
+                    month = Some(Date::take_name(&mut rest, &MONTHS, len).ok_or_else(mismatch)? as u8 + 1);
+                    positions[1] = position;
+                }
+                Some(spec @ 'A') | Some(spec @ 'a') => {
+                    let len = if spec == 'A' { None } else { Some(3) };
+                    weekday = Some(Date::take_name(&mut rest, &WEEKDAYS, len).ok_or_else(mismatch)?);
+                    positions[3] = position;
+                }
+                Some('%') => rest = rest.strip_prefix('%').ok_or_else(mismatch)?,
+                _ => return Err(ParseError::at(s, 0, format!("{}: Invalid format pattern", pattern))),
+            }
+        }
+        if !rest.is_empty() {
+            return Err(ParseError::at(s, s.len() - rest.len(),
+                                      format!("{}: Does not match the pattern {}", s, pattern)));
+        }
+        match (year, month, day) {
+            (Some(year), Some(month), Some(day)) => {
+                let date = Date::validated(s, Date::new(year, month, day),
+                                           [positions[0], positions[1], positions[2]])?;
+                let actual = date.weekday().ok().map(|weekday| weekday.days_from_monday() as usize);
+                match weekday {
+                    Some(weekday) if actual != Some(weekday) => {
+                        Err(ParseError::at(s, positions[3], format!("{}: Wrong weekday", s)))
+                    }
+                    _ => Ok(date),
+                }
+            }
+            _ => Err(ParseError::at(s, 0,
+                                    format!("{}: The pattern needs a year, a month and a day", pattern))),
+        }
+    }
+
+    /// Consume *len* ASCII digits from the start of *rest* and return their value
+    fn take_digits(rest: &mut &str, len: usize) -> Option<u32> {
+        let value = Date::numeric_field(rest.get(..len)?, len, len)?;
+        *rest = &rest[len..];
+        Some(value)
+    }
+
+    /// Consume one of *names* (or its first *len* letters) from the start of *rest*
+    /// and return its index
+    fn take_name(rest: &mut &str, names: &[&str], len: Option<usize>) -> Option<usize> {
+        let len = |name: &str| len.unwrap_or(name.len());
+        let index = names.iter().position(|&name| rest.starts_with(&name[..len(name)]))?;
+        *rest = &rest[len(names[index])..];
+        Some(index)
+    }
+
+    /// Numeric value of *field* if it has between *min_len* and *max_len* ASCII digits
+    fn numeric_field(field: &str, min_len: usize, max_len: usize) -> Option<u32> {
+        if field.len() < min_len || field.len() > max_len
+            || !field.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some(Date::digits_value(field))
+    }
+
+    /// Numeric value of a string already known to hold only ASCII digits,
+    /// saturating at *u32::MAX* for longer numbers
+    fn digits_value(digits: &str) -> u32 {
+        digits.bytes().fold(0, |acc: u32, b| acc.saturating_mul(10).saturating_add((b - b'0') as u32))
+    }
+}
+
+#[test]
+fn test_leap_years() {
+    assert!(Date::is_leap(2000));
+    assert!(!Date::is_leap(2001));
+    assert!(!Date::is_leap(2100));
+    assert!(Date::is_leap(2004));
+    assert!(Date::is_leap(100));
+    assert!(Date::is_leap(-1));
+    assert!(!Date::is_leap(-4));
+}
+
+#[test]
+fn test_invalid_dates() {
+    let date = Date::new(1979, 2, 29);
+    assert!(date.is_valid().is_err());
+
+    let date = Date::new(1979, 1, 0);
+    assert!(date.is_valid().is_err());
+
+    let date = Date::new(0, 1, 1);
+    assert!(date.is_valid().is_err());
+
+    let date = Date::new(1, 1, 41);
+    assert!(date.is_valid().is_err());
+
+    let date = Date::new(-4, 15, 1);
+    assert!(date.is_valid().is_err());
+
+    let date = Date::new(1582, 10, 11);
+    assert!(date.is_valid().is_err());
+}
+
+#[test]
+fn test_days_in_year() {
+    assert_eq!(Date::year_days(1977),365);
+    assert_eq!(Date::year_days(1978),365);
+    assert_eq!(Date::year_days(1980),366);
+    assert_eq!(Date::year_days(2000),366);
+    assert_eq!(Date::year_days(2100),365);
+    assert_eq!(Date::year_days(1582),355);
+    assert_eq!(Date::year_days(0),0);
+    assert_eq!(Date::year_days(-1),366);
+}
+
+#[test]
+fn test_days_between_dates() {
+    let first = Date::new(1950, 1, 1);
+    let last = Date::new(1950, 12, 31);
+    assert_eq!(Date::days_between_dates(&first, &last), Ok(364));
+
+    let first = Date::new(1950, 1, 1);
+    let last = Date::new(1950, 1, 2);
+    assert_eq!(Date::days_between_dates(&first, &last), Ok(1));
+
+    assert_eq!(Date::days_between_dates(&first, &first), Ok(0));
+
+    let first = Date::new(1980, 1, 1);
+    let last = Date::new(1981, 1, 1);
+    assert_eq!(Date::days_between_dates(&first, &last), Ok(366));
+    assert_eq!(Date::days_between_dates(&last, &first), Ok(-366));
+
+    let first = Date::new(1977, 10, 1);
+    let last = Date::new(2021, 7, 22);
+    assert_eq!(Date::days_between_dates(&first, &last), Ok(16000));
+
+
+    let first = Date::new(2000, 1, 1);
+    let last = Date::new(2400, 1, 1);
+    assert_eq!(Date::days_between_dates(&first, &last), Ok(146097));
+
+    let first = Date::new(2000, 1, 1);
+    let last = Date::new(2400, 1, 1);
+    assert_eq!(Date::days_between_dates(&first, &last), Ok(146097));
+
+    let first = Date::new(1582, 1, 1);
+    let last = Date::new(1982, 1, 1);
+    assert_eq!(Date::days_between_dates(&first, &last), Ok(146087));
+
+    let first = Date::new(0, 1, 1);
+    assert!(Date::days_between_dates(&first, &last).is_err());
+
+}
+
+#[test]
+fn test_parse_compact() {
+    assert_eq!(Date::parse_compact("20210722"), Ok(Date::new(2021, 7, 22)));
+    assert_eq!(Date::parse_compact("-00440315"), Ok(Date::new(-44, 3, 15)));
+
+    assert!(Date::parse_compact("2021072").is_err());
+    assert!(Date::parse_compact("202107221").is_err());
+    assert!(Date::parse_compact("2021-7-22").is_err());
+    assert!(Date::parse_compact("2021O722").is_err());
+    assert!(Date::parse_compact("+20210722").is_err());
+    assert!(Date::parse_compact("").is_err());
+    assert!(Date::parse_compact("20210230").is_err());
+    assert!(Date::parse_compact("00000101").is_err());
+}
+
+#[test]
+fn test_compact() {
+    assert_eq!(Date::new(2021, 7, 22).compact(), "20210722");
+    assert_eq!(Date::new(33, 1, 5).compact(), "00330105");
+    assert_eq!(Date::new(-44, 3, 15).compact(), "-00440315");
+
+    for date in &[Date::new(2021, 7, 22), Date::new(1, 1, 1), Date::new(-753, 4, 21),
+                  Date::new(1582, 10, 15), Date::new(2000, 2, 29)] {
+        assert_eq!(Date::parse_compact(&date.compact()), Ok(date.clone()));
+    }
+
+    //Outside years 1 to 9999 the text neither sorts by date nor always reads back
+    assert!(Date::new(-100, 1, 1).compact() > Date::new(-44, 3, 15).compact());
+    assert_eq!(Date::new(12_021, 7, 22).compact(), "120210722");
+    assert!(Date::parse_compact(&Date::new(12_021, 7, 22).compact()).is_err());
+}
+
+#[test]
+fn test_days_since_start_of_year() {
+    assert_eq!(Date::new(2021, 1, 1).days_since_start_of_year(), Ok(0));
+    assert_eq!(Date::new(2021, 12, 31).days_since_start_of_year(), Ok(364));
+    assert_eq!(Date::new(2000, 12, 31).days_since_start_of_year(), Ok(365));
+
+    assert_eq!(Date::new(1582, 10, 4).days_since_start_of_year(), Ok(276));
+    assert_eq!(Date::new(1582, 10, 15).days_since_start_of_year(), Ok(277));
+    assert_eq!(Date::new(1582, 11, 1).days_since_start_of_year(), Ok(294));
+    assert_eq!(Date::new(1582, 12, 31).days_since_start_of_year(), Ok(Date::year_days(1582) - 1));
+    assert!(Date::new(2021, 13, 1).days_since_start_of_year().is_err());
+    assert!(Date::new(2021, 0, 1).days_since_start_of_year().is_err());
+}
+
+#[test]
+fn test_fiscal_year() {
+    assert_eq!(Date::new(2021, 6, 30).fiscal_year(7), Ok(2021));
+    assert_eq!(Date::new(2021, 6, 30).fiscal_quarter(7), Ok(4));
+    assert_eq!(Date::new(2021, 7, 1).fiscal_year(7), Ok(2022));
+    assert_eq!(Date::new(2021, 7, 1).fiscal_quarter(7), Ok(1));
+    assert_eq!(Date::new(2021, 12, 31).fiscal_quarter(7), Ok(2));
+
+    assert_eq!(Date::new(2021, 3, 31).fiscal_year(4), Ok(2021));
+    assert_eq!(Date::new(2021, 3, 31).fiscal_quarter(4), Ok(4));
+    assert_eq!(Date::new(2021, 4, 1).fiscal_year(4), Ok(2022));
+    assert_eq!(Date::new(2021, 4, 1).fiscal_quarter(4), Ok(1));
+    assert_eq!(Date::new(2022, 1, 15).fiscal_quarter(4), Ok(4));
+
+    assert_eq!(Date::new(2021, 12, 31).fiscal_year(1), Ok(2021));
+    assert_eq!(Date::new(2021, 12, 31).fiscal_quarter(1), Ok(4));
+    assert_eq!(Date::new(-1, 10, 1).fiscal_year(7), Ok(1));
+
+    assert!(Date::new(2021, 7, 1).fiscal_year(0).is_err());
+    assert!(Date::new(2021, 7, 1).fiscal_quarter(13).is_err());
+    assert_eq!(Date::new(i32::MAX, 6, 30).fiscal_year(7), Ok(i32::MAX));
+    assert!(Date::new(i32::MAX, 12, 1).fiscal_year(7).is_err());
+    assert!(Date::new(2021, 250, 1).fiscal_quarter(7).is_err());
+    assert!(Date::new(2021, 13, 1).fiscal_year(7).is_err());
+}
+
+#[test]
+fn test_default() {
+    let date = Date::default();
+    assert!(date.is_valid().is_ok());
+    assert_eq!(date, Date::new(1970, 1, 1));
+}
+
+#[test]
+fn test_add_days() {
+    let date = Date::new(2021, 7, 22);
+    assert_eq!(date.add_days(0), Ok(date.clone()));
+    assert_eq!(date.add_days(10), Ok(Date::new(2021, 8, 1)));
+    assert_eq!(date.add_days(-22), Ok(Date::new(2021, 6, 30)));
+    assert_eq!(date.add_days(365), Ok(Date::new(2022, 7, 22)));
+    assert_eq!(Date::new(2000, 2, 28).add_days(1), Ok(Date::new(2000, 2, 29)));
+    assert_eq!(Date::new(1582, 10, 4).add_days(1), Ok(Date::new(1582, 10, 15)));
+    assert_eq!(Date::new(1582, 10, 15).add_days(-1), Ok(Date::new(1582, 10, 4)));
+    assert_eq!(Date::new(-1, 12, 31).add_days(1), Ok(Date::new(1, 1, 1)));
+    assert_eq!(Date::new(1, 1, 1).add_days(-1), Ok(Date::new(-1, 12, 31)));
+
+    let first = Date::new(1977, 10, 1);
+    assert_eq!(first.add_days(16000), Ok(Date::new(2021, 7, 22)));
+    assert!(Date::new(2021, 2, 29).add_days(1).is_err());
+}
+
+#[test]
+fn test_weekday() {
+    assert_eq!(Date::new(1970, 1, 1).weekday(), Ok(Weekday::Thursday));
+    assert_eq!(Date::new(2021, 7, 22).weekday(), Ok(Weekday::Thursday));
+    assert_eq!(Date::new(2001, 1, 1).weekday(), Ok(Weekday::Monday));
+    assert_eq!(Date::new(1582, 10, 4).weekday(), Ok(Weekday::Thursday));
+    assert_eq!(Date::new(1582, 10, 15).weekday(), Ok(Weekday::Friday));
+    assert!(Date::new(1582, 10, 10).weekday().is_err());
+}
+
+#[test]
+fn test_start_and_end_of_week() {
+    let wednesday = Date::new(2021, 7, 21);
+    assert_eq!(wednesday.start_of_week(Weekday::Monday), Ok(Date::new(2021, 7, 19)));
+    assert_eq!(wednesday.end_of_week(Weekday::Monday), Ok(Date::new(2021, 7, 25)));
+    assert_eq!(wednesday.start_of_week(Weekday::Sunday), Ok(Date::new(2021, 7, 18)));
+    assert_eq!(wednesday.end_of_week(Weekday::Sunday), Ok(Date::new(2021, 7, 24)));
+
+    let wednesday = Date::new(2020, 12, 30);
+    assert_eq!(wednesday.start_of_week(Weekday::Monday), Ok(Date::new(2020, 12, 28)));
+    assert_eq!(wednesday.end_of_week(Weekday::Monday), Ok(Date::new(2021, 1, 3)));
+
+    let wednesday = Date::new(2021, 9, 1);
+    assert_eq!(wednesday.start_of_week(Weekday::Monday), Ok(Date::new(2021, 8, 30)));
+    assert_eq!(wednesday.start_of_week(Weekday::Wednesday), Ok(wednesday));
+
+    let friday = Date::new(1582, 10, 15);
+    assert_eq!(friday.start_of_week(Weekday::Monday), Ok(Date::new(1582, 10, 1)));
+    assert_eq!(friday.end_of_week(Weekday::Monday), Ok(Date::new(1582, 10, 17)));
+}
+
+#[test]
+fn test_validation_order() {
+    assert_eq!(Date::new(0, 13, 40).is_valid(), Err("Year 0 does not exist".into()));
+    assert_eq!(Date::new(1582, 13, 10).is_valid(), Err("Invalid month".into()));
+    assert_eq!(Date::new(1582, 0, 10).is_valid(), Err("Invalid month".into()));
+    assert_eq!(Date::new(1582, 10, 10).is_valid(), Err("October 10, 1582 does not exist".into()));
+    assert_eq!(Date::new(1582, 10, 40).is_valid(), Err("October 40, 1582: Invalid day".into()));
+    assert_eq!(Date::new(1583, 10, 10).is_valid(), Ok(()));
+    assert_eq!(Date::new(2021, 2, 29).is_valid(), Err("February 29, 2021: Invalid day".into()));
+    assert_eq!(Date::new(2021, 2, 0).is_valid(), Err("February 0, 2021: Invalid day".into()));
+}
+
+#[test]
+fn test_julian_day() {
+    assert_eq!(Date::new(2000, 1, 1).to_julian_day(), Ok(2_451_545));
+    assert_eq!(Date::new(1970, 1, 1).to_julian_day(), Ok(2_440_588));
+    assert_eq!(Date::new(1582, 10, 4).to_julian_day(), Ok(2_299_160));
+    assert_eq!(Date::new(1582, 10, 15).to_julian_day(), Ok(2_299_161));
+    assert_eq!(Date::new(-4713, 1, 1).to_julian_day(), Ok(0));
+
+    assert_eq!(Date::from_julian_day(2_451_545), Ok(Date::new(2000, 1, 1)));
+    assert_eq!(Date::from_julian_day(2_299_161), Ok(Date::new(1582, 10, 15)));
+    assert_eq!(Date::from_julian_day(0), Ok(Date::new(-4713, 1, 1)));
+    assert!(Date::from_julian_day(i64::MAX).is_err());
+}
+
+#[test]
+fn test_julian_date_fractions() {
+    let date = Date::new(2000, 1, 1);
+    let noon = date.to_julian_date_noon().unwrap();
+    let midnight = date.to_julian_date_midnight().unwrap();
+    assert_eq!(noon, JulianDate(2_451_545.0));
+    assert_eq!(midnight, JulianDate(2_451_544.5));
+    assert_eq!(noon.day_fraction(), 0.5);
+    assert_eq!(midnight.day_fraction(), 0.0);
+    assert_eq!(noon.julian_day(), 2_451_545);
+    assert_eq!(midnight.julian_day(), 2_451_545);
+    assert_eq!(noon.to_date(), Ok(date.clone()));
+    assert_eq!(midnight.to_date(), Ok(date));
+
+    let next_midnight = noon.add(0.5);
+    assert_eq!(next_midnight.day_fraction(), 0.0);
+    assert_eq!(next_midnight.to_date(), Ok(Date::new(2000, 1, 2)));
+
+    assert!(JulianDate(f64::NAN).to_date().is_err());
+    assert!(JulianDate(f64::INFINITY).to_date().is_err());
+    assert!(JulianDate(f64::NEG_INFINITY).to_date().is_err());
+    assert!(noon.add(f64::NAN).to_date().is_err());
+}
+
+#[test]
+fn test_month_name() {
+    assert_eq!(Date::new(2000, 1, 1).month_name(), Some("January"));
+    assert_eq!(Date::new(2000, 12, 1).month_name(), Some("December"));
+    assert_eq!(Date::new(2000, 0, 1).month_name(), None);
+    assert_eq!(Date::new(2000, 13, 1).month_name(), None);
+
+    assert_eq!(Date::new(2000, 13, 1).to_string(), "<invalid month> 1, 2000");
+    assert_eq!(Date::new(-5, 0, 1).to_string(), "<invalid month> 1, 5 BC");
+    assert_eq!(format!("{:?}", Date::new(2000, 13, 1)), "Date(2000-13-01)");
+    assert!(Date::new(2000, 13, 1).long_form().is_err());
+    assert!(Date::new(2000, 0, 1).format("%B").is_err());
+    assert_eq!(Date::new(2000, 13, 1).localized_month_name(&Locale::new([None; 12])), None);
+}
+
+#[test]
+fn test_julian_day_closed_form() {
+    let epoch = Date::default();
+    for days in (-800_000..800_000).step_by(97) {
+        let date = epoch.add_days(days).unwrap();
+        assert_eq!(date.to_julian_day(), Ok(UNIX_EPOCH_JULIAN_DAY + days as i64));
+        assert_eq!(Date::from_julian_day(UNIX_EPOCH_JULIAN_DAY + days as i64), Ok(date));
+    }
+}
+
+#[test]
+fn test_checked_add_days() {
+    let date = Date::new(2021, 7, 22);
+    assert_eq!(date.checked_add_days(10), Some(Date::new(2021, 8, 1)));
+    assert_eq!(date.checked_add_days(-16000), Some(Date::new(1977, 10, 1)));
+    assert_eq!(Date::new(1582, 10, 4).checked_add_days(1), Some(Date::new(1582, 10, 15)));
+    assert_eq!(Date::new(-1, 12, 31).checked_add_days(1), Some(Date::new(1, 1, 1)));
+
+    assert_eq!(Date::MAX.checked_add_days(0), Some(Date::MAX));
+    assert_eq!(Date::MIN.checked_add_days(0), Some(Date::MIN));
+    assert_eq!(Date::MAX.checked_add_days(-1), Some(Date::new(i32::MAX, 12, 30)));
+    assert_eq!(Date::MIN.checked_add_days(1), Some(Date::new(i32::MIN, 1, 2)));
+    assert_eq!(Date::MAX.checked_add_days(1), None);
+    assert_eq!(Date::MIN.checked_add_days(-1), None);
+    assert_eq!(date.checked_add_days(i64::MAX), None);
+    assert_eq!(date.checked_add_days(i64::MIN), None);
+    assert_eq!(Date::new(2021, 2, 29).checked_add_days(1), None);
+}
+
+#[test]
+fn test_proleptic_gregorian_leap_years() {
+    assert!(Date::is_proleptic_gregorian_leap(2000));
+    assert!(!Date::is_proleptic_gregorian_leap(2100));
+    assert!(Date::is_proleptic_gregorian_leap(1600));
+    assert!(Date::is_proleptic_gregorian_leap(-1));
+
+    assert!(Date::is_leap(1500));
+    assert!(!Date::is_proleptic_gregorian_leap(1500));
+    assert!(Date::is_leap(-101));
+    assert!(!Date::is_proleptic_gregorian_leap(-101));
+    assert!(Date::is_proleptic_gregorian_leap(1200));
+}
+
+#[test]
+fn test_month_days_in() {
+    assert_eq!(Month::February.days_in(2024), 29);
+    assert_eq!(Month::February.days_in(2023), 28);
+    assert_eq!(Month::February.days_in(1900), 28);
+    assert_eq!(Month::February.days_in(1500), 29);
+    assert_eq!(Month::October.days_in(1582), 21);
+    assert_eq!(Month::October.days_in(1583), 31);
+    assert_eq!(Month::April.days_in(2021), 30);
+    assert_eq!(Month::December.number(), 12);
+    assert_eq!(Month::March.name(), "March");
+}
+
+#[test]
+fn test_rata_die() {
+    assert_eq!(Date::new(1, 1, 1).to_rata_die(), 1);
+    assert_eq!(Date::new(-1, 12, 31).to_rata_die(), 0);
+    assert_eq!(Date::from_rata_die(1), Ok(Date::new(1, 1, 1)));
+
+    for date in &[Date::new(1970, 1, 1), Date::new(1582, 10, 4), Date::new(1582, 10, 15),
+                  Date::new(2000, 2, 29), Date::new(-44, 3, 15), Date::MIN, Date::MAX] {
+        assert_eq!(Date::from_rata_die(date.to_rata_die()), Ok(date.clone()));
+    }
+    assert_eq!(Date::new(1582, 10, 15).to_rata_die() - Date::new(1582, 10, 4).to_rata_die(), 1);
+    assert_eq!(Date::new(2021, 7, 22).to_rata_die() - Date::new(1977, 10, 1).to_rata_die(), 16000);
+
+    assert!(Date::from_rata_die(Date::MAX.to_rata_die() + 1).is_err());
+    assert!(Date::from_rata_die(i64::MAX).is_err());
+}
+
+#[test]
+fn test_gregorian_gap_boundaries() {
+    for day in 1..=4 {
+        assert!(Date::new(1582, 10, day).is_valid().is_ok());
+    }
+    for day in 5..=14 {
+        assert!(Date::new(1582, 10, day).is_valid().is_err());
+    }
+    for day in 15..=31 {
+        assert!(Date::new(1582, 10, day).is_valid().is_ok());
+    }
+    assert!(Date::new(1582, 10, 32).is_valid().is_err());
+    assert_eq!(Date::year_days(1582), 355);
+    assert_eq!(Date::new(1582, 10, 15).day_of_year(), Date::new(1582, 10, 4).day_of_year() + 1);
+}
+
+#[test]
+fn test_range() {
+    let dates: Vec<Date> = Date::range(Date::new(2020, 12, 30), Date::new(2021, 1, 2)).collect();
+    assert_eq!(dates, vec![Date::new(2020, 12, 30), Date::new(2020, 12, 31), Date::new(2021, 1, 1)]);
+    assert_eq!(Date::range(Date::new(2021, 1, 2), Date::new(2021, 1, 2)).count(), 0);
+    assert_eq!(Date::range(Date::new(2021, 1, 3), Date::new(2021, 1, 2)).count(), 0);
+    assert_eq!(Date::range(Date::new(1582, 10, 1), Date::new(1582, 11, 1)).count(), 21);
+}
+
+#[test]
+fn test_count_matching() {
+    let is_sunday = |date: &Date| date.weekday() == Ok(Weekday::Sunday);
+    assert_eq!(Date::count_matching(Date::new(2021, 8, 1), Date::new(2021, 9, 1), is_sunday), Ok(5));
+    assert_eq!(Date::count_matching(Date::new(1582, 10, 1), Date::new(1582, 11, 1), is_sunday), Ok(3));
+
+    let is_month_end = |date: &Date| date.checked_add_days(1).is_some_and(|next| next.day == 1);
+    assert_eq!(Date::count_matching(Date::new(2021, 1, 1), Date::new(2022, 1, 1), is_month_end), Ok(12));
+
+    assert!(Date::count_matching(Date::new(2021, 2, 30), Date::new(2022, 1, 1), is_month_end).is_err());
+}
+
+#[test]
+fn test_display_padding() {
+    let date = Date::new(2001, 2, 28);
+    assert_eq!(format!("{}", date), "February 28, 2001");
+    assert_eq!(format!("{:>20}", date), "   February 28, 2001");
+    assert_eq!(format!("{:<20}|", date), "February 28, 2001   |");
+    assert_eq!(format!("{:*^21}", date), "**February 28, 2001**");
+    assert_eq!(format!("{:5}", date), "February 28, 2001");
+    assert_eq!(format!("{:>15}", Date::new(-5, 3, 1)), "  March 1, 5 BC");
+}
+
+#[test]
+fn test_try_new() {
+    assert_eq!(Date::try_new(2021, 7, 22), Ok(Date::new(2021, 7, 22)));
+    assert_eq!(Date::try_new(0, 7, 22), Err(DateError::YearZero));
+    assert_eq!(Date::try_new(2021, 13, 22), Err(DateError::InvalidMonth));
+    assert_eq!(Date::try_new(2021, 2, 29), Err(DateError::InvalidDay(Date::new(2021, 2, 29))));
+    assert_eq!(Date::try_new(1582, 10, 5), Err(DateError::SkippedByReform {
+        date: Date::new(1582, 10, 5),
+        previous: Date::new(1582, 10, 4),
+        next: Date::new(1582, 10, 15),
+    }));
+    assert_eq!(DateError::InvalidDay(Date::new(2021, 2, 29)).to_string(), "February 29, 2021: Invalid day");
+}
+
+#[test]
+fn test_iso_week() {
+    assert_eq!(Date::new(2021, 7, 22).iso_week(), Ok((2021, 29)));
+    assert_eq!(Date::new(2021, 1, 1).iso_week(), Ok((2020, 53)));
+    assert_eq!(Date::new(2021, 1, 4).iso_week(), Ok((2021, 1)));
+    assert_eq!(Date::new(2019, 12, 30).iso_week(), Ok((2020, 1)));
+    assert_eq!(Date::new(2020, 12, 31).iso_week(), Ok((2020, 53)));
+}
+
+#[test]
+fn test_period_key() {
+    let date = Date::new(2021, 7, 22);
+    assert_eq!(date.period_key(Period::Day), Ok("2021-07-22".into()));
+    assert_eq!(date.period_key(Period::Week), Ok("2021-W29".into()));
+    assert_eq!(date.period_key(Period::Month), Ok("2021-07".into()));
+    assert_eq!(date.period_key(Period::Quarter), Ok("2021-Q3".into()));
+    assert_eq!(date.period_key(Period::Year), Ok("2021".into()));
+
+    assert_eq!(Date::new(2021, 1, 1).period_key(Period::Week), Ok("2020-W53".into()));
+    assert_eq!(Date::new(2019, 12, 31).period_key(Period::Week), Ok("2020-W01".into()));
+    assert_eq!(Date::new(-44, 3, 15).period_key(Period::Quarter), Ok("-0044-Q1".into()));
+    assert!(Date::new(2021, 2, 29).period_key(Period::Month).is_err());
+}
+
+#[test]
+fn test_succ_and_pred() {
+    assert_eq!(Date::new(2021, 12, 31).succ(), Some(Date::new(2022, 1, 1)));
+    assert_eq!(Date::new(2022, 1, 1).pred(), Some(Date::new(2021, 12, 31)));
+    assert_eq!(Date::new(1582, 10, 4).succ(), Some(Date::new(1582, 10, 15)));
+    assert_eq!(Date::new(1582, 10, 15).pred(), Some(Date::new(1582, 10, 4)));
+    assert_eq!(Date::new(1, 1, 1).pred(), Some(Date::new(-1, 12, 31)));
+
+    assert_eq!(Date::MAX.succ(), None);
+    assert_eq!(Date::MIN.pred(), None);
+    assert_eq!(Date::MAX.pred().and_then(|date| date.succ()), Some(Date::MAX));
+    assert_eq!(Date::new(1582, 10, 10).succ(), None);
+}
+
+#[test]
+fn test_parse_with_pivot() {
+    assert_eq!(Date::parse_with_pivot("07/22/21", 69), Ok(Date::new(2021, 7, 22)));
+    assert_eq!(Date::parse_with_pivot("07/22/68", 69), Ok(Date::new(2068, 7, 22)));
+    assert_eq!(Date::parse_with_pivot("07/22/69", 69), Ok(Date::new(1969, 7, 22)));
+    assert_eq!(Date::parse_with_pivot("7/4/99", 69), Ok(Date::new(1999, 7, 4)));
+    assert_eq!(Date::parse_with_pivot("01/01/00", 0), Ok(Date::new(1900, 1, 1)));
+    assert_eq!(Date::parse_with_pivot("12/31/99", 100), Ok(Date::new(2099, 12, 31)));
+
+    assert!(Date::parse_with_pivot("07/22/2021", 69).is_err());
+    assert!(Date::parse_with_pivot("07-22-21", 69).is_err());
+    assert!(Date::parse_with_pivot("07/22", 69).is_err());
+    assert!(Date::parse_with_pivot("13/22/21", 69).is_err());
+    assert!(Date::parse_with_pivot("02/29/21", 69).is_err());
+    assert!(Date::parse_with_pivot("07/22/21", 101).is_err());
+}
+
+#[test]
+fn test_weeks_between() {
+    let first = Date::new(2021, 7, 1);
+    assert_eq!(Date::weeks_between(&first, &Date::new(2021, 7, 29)), Ok((4, 0)));
+    assert_eq!(Date::weeks_between(&first, &Date::new(2021, 7, 11)), Ok((1, 3)));
+    assert_eq!(Date::weeks_between(&first, &first), Ok((0, 0)));
+    assert_eq!(Date::weeks_between(&Date::new(2021, 7, 29), &first), Ok((-4, 0)));
+    assert_eq!(Date::weeks_between(&Date::new(2021, 7, 11), &first), Ok((-2, 4)));
+    assert_eq!(Date::weeks_between(&Date::new(1582, 10, 4), &Date::new(1582, 10, 22)), Ok((1, 1)));
+    assert!(Date::weeks_between(&first, &Date::new(2021, 2, 29)).is_err());
+    assert!(Date::weeks_between(&Date::new(-7_000_000, 1, 1), &first).is_err());
+}
+
+#[test]
+fn test_calendar_year() {
+    for &year in &[2021, 2020, 1582, -1] {
+        let dates = Date::calendar_year(year).unwrap();
+        assert_eq!(dates.len() as i32, Date::year_days(year));
+        assert_eq!(dates[0], Date::new(year, 1, 1));
+        assert_eq!(dates[dates.len() - 1], Date::new(year, 12, 31));
+    }
+    assert_eq!(Date::calendar_year(1582).unwrap().len(), 355);
+    assert_eq!(Date::calendar_year(i32::MAX).unwrap().len(), 365);
+    assert!(Date::calendar_year(0).is_err());
+
+    let mut calendar = std::collections::BTreeMap::new();
+    for date in Date::calendar_year(2021).unwrap().into_iter().rev() {
+        let weekday = date.weekday().unwrap();
+        calendar.insert(date, weekday);
+    }
+    assert_eq!(calendar.keys().next(), Some(&Date::new(2021, 1, 1)));
+    assert_eq!(calendar.get(&Date::new(2021, 7, 22)), Some(&Weekday::Thursday));
+    let july: Vec<&Date> = calendar.range(Date::new(2021, 7, 1)..Date::new(2021, 8, 1)).map(|(date, _)| date).collect();
+    assert_eq!(july.len(), 31);
+}
+
+#[test]
+fn test_historically_valid() {
+    let date = Date::new(1582, 10, 10);
+    assert!(date.is_historically_valid(&Calendar::ROME).is_err());
+    assert!(date.is_historically_valid(&Calendar::BRITAIN).is_err());
+
+    let date = Date::new(1752, 9, 3);
+    assert!(date.is_valid().is_ok());
+    assert!(date.is_historically_valid(&Calendar::ROME).is_ok());
+    assert_eq!(date.is_historically_valid(&Calendar::BRITAIN),
+               Err("September 3, 1752 was skipped by the calendar reform".into()));
+    assert!(Date::new(1752, 9, 13).is_historically_valid(&Calendar::BRITAIN).is_err());
+    assert!(Date::new(1752, 9, 2).is_historically_valid(&Calendar::BRITAIN).is_ok());
+    assert!(Date::new(1752, 9, 14).is_historically_valid(&Calendar::BRITAIN).is_ok());
+}
+
+#[test]
+fn test_from_unix_seconds() {
+    assert_eq!(Date::from_unix_days(0), Ok(Date::new(1970, 1, 1)));
+    assert_eq!(Date::from_unix_days(18_830), Ok(Date::new(2021, 7, 22)));
+    assert_eq!(Date::from_unix_days(-1), Ok(Date::new(1969, 12, 31)));
+    assert!(Date::from_unix_days(i64::MAX).is_err());
+
+    assert_eq!(Date::from_unix_seconds(1_626_955_200), Ok(Date::new(2021, 7, 22)));
+    assert_eq!(Date::from_unix_seconds(1_626_912_000), Ok(Date::new(2021, 7, 22)));
+    assert_eq!(Date::from_unix_seconds(1_626_911_999), Ok(Date::new(2021, 7, 21)));
+    assert_eq!(Date::from_unix_seconds(0), Ok(Date::new(1970, 1, 1)));
+    assert_eq!(Date::from_unix_seconds(-1), Ok(Date::new(1969, 12, 31)));
+    assert_eq!(Date::from_unix_seconds(-86_400), Ok(Date::new(1969, 12, 31)));
+    assert_eq!(Date::from_unix_seconds(-86_401), Ok(Date::new(1969, 12, 30)));
+    assert!(Date::from_unix_seconds(i64::MIN).is_err());
+}
+
+#[test]
+fn test_range_inclusive() {
+    let start = Date::new(2021, 1, 1);
+    let end = Date::new(2021, 1, 31);
+    let dates: Vec<Date> = Date::range_inclusive(start.clone(), end.clone()).collect();
+    assert_eq!(dates.len(), Date::range(start.clone(), end.clone()).count() + 1);
+    assert_eq!(dates.len(), 31);
+    assert_eq!(dates.last(), Some(&end));
+
+    assert_eq!(Date::range_inclusive(start.clone(), start.clone()).collect::<Vec<Date>>(), vec![start.clone()]);
+    assert_eq!(Date::range_inclusive(end, start).count(), 0);
+    assert_eq!(Date::range_inclusive(Date::new(1582, 10, 1), Date::new(1582, 10, 31)).count(), 21);
+    assert_eq!(Date::range_inclusive(Date::new(i32::MAX, 12, 30), Date::MAX).count(), 2);
+}
+
+#[test]
+fn test_nth_weekday_of_month() {
+    assert_eq!(Date::nth_weekday_of_month(2021, 1, Weekday::Monday, 1), Ok(Date::new(2021, 1, 4)));
+    assert_eq!(Date::nth_weekday_of_month(2021, 1, Weekday::Monday, 3), Ok(Date::new(2021, 1, 18)));
+    assert_eq!(Date::nth_weekday_of_month(2021, 1, Weekday::Friday, 1), Ok(Date::new(2021, 1, 1)));
+    assert_eq!(Date::nth_weekday_of_month(2021, 1, Weekday::Friday, 5), Ok(Date::new(2021, 1, 29)));
+    assert_eq!(Date::nth_weekday_of_month(1582, 10, Weekday::Friday, 1), Ok(Date::new(1582, 10, 15)));
+    assert_eq!(Date::nth_weekday_of_month(1582, 10, Weekday::Friday, 3), Ok(Date::new(1582, 10, 29)));
+    assert!(Date::nth_weekday_of_month(1582, 10, Weekday::Friday, 4).is_err());
+
+    assert_eq!(Date::nth_weekday_of_month(2021, 2, Weekday::Friday, 5),
+               Err("There is no Friday number 5 in February 2021".into()));
+    assert!(Date::nth_weekday_of_month(2021, 12, Weekday::Monday, 5).is_err());
+    assert!(Date::nth_weekday_of_month(2021, 1, Weekday::Monday, 0).is_err());
+    assert!(Date::nth_weekday_of_month(2021, 13, Weekday::Monday, 1).is_err());
+}
+
+#[test]
+fn test_last_weekday_of_month() {
+    assert_eq!(Date::new(2021, 7, 5).end_of_month(), Ok(Date::new(2021, 7, 31)));
+    assert_eq!(Date::new(2020, 2, 5).end_of_month(), Ok(Date::new(2020, 2, 29)));
+    assert_eq!(Date::new(1582, 10, 1).end_of_month(), Ok(Date::new(1582, 10, 31)));
+
+    assert_eq!(Date::last_weekday_of_month(2021, 7, Weekday::Friday), Ok(Date::new(2021, 7, 30)));
+    assert_eq!(Date::last_weekday_of_month(2021, 7, Weekday::Saturday), Ok(Date::new(2021, 7, 31)));
+    assert_eq!(Date::last_weekday_of_month(2020, 2, Weekday::Friday), Ok(Date::new(2020, 2, 28)));
+    assert_eq!(Date::last_weekday_of_month(2020, 2, Weekday::Saturday), Ok(Date::new(2020, 2, 29)));
+    assert_eq!(Date::last_weekday_of_month(2021, 2, Weekday::Saturday), Ok(Date::new(2021, 2, 27)));
+    assert_eq!(Date::last_weekday_of_month(1582, 10, Weekday::Thursday), Ok(Date::new(1582, 10, 28)));
+    assert!(Date::last_weekday_of_month(2021, 0, Weekday::Friday).is_err());
+}
+
+#[test]
+fn test_semester() {
+    let june = Date::new(2021, 6, 30);
+    let july = Date::new(2021, 7, 1);
+    assert_eq!(june.semester(), 1);
+    assert_eq!(july.semester(), 2);
+    assert_eq!(Date::new(2021, 1, 1).semester(), 1);
+    assert_eq!(Date::new(2021, 12, 31).semester(), 2);
+
+    assert_eq!(june.first_day_of_semester(), Date::new(2021, 1, 1));
+    assert_eq!(june.last_day_of_semester(), june);
+    assert_eq!(july.first_day_of_semester(), july);
+    assert_eq!(july.last_day_of_semester(), Date::new(2021, 12, 31));
+}
+
+#[test]
+fn test_round_to() {
+    let date = Date::new(2021, 7, 12);
+    assert_eq!(date.round_down(Period::Month, Weekday::Monday), Ok(Date::new(2021, 7, 1)));
+    assert_eq!(date.round_up(Period::Month, Weekday::Monday), Ok(Date::new(2021, 8, 1)));
+    assert_eq!(date.round_to(Period::Month), Ok(Date::new(2021, 7, 1)));
+    assert_eq!(Date::new(2021, 7, 20).round_to(Period::Month), Ok(Date::new(2021, 8, 1)));
+    assert_eq!(Date::new(2021, 12, 20).round_to(Period::Month), Ok(Date::new(2022, 1, 1)));
+    assert_eq!(Date::new(2021, 7, 1).round_up(Period::Month, Weekday::Monday), Ok(Date::new(2021, 7, 1)));
+
+    assert_eq!(date.round_down(Period::Quarter, Weekday::Monday), Ok(Date::new(2021, 7, 1)));
+    assert_eq!(date.round_up(Period::Quarter, Weekday::Monday), Ok(Date::new(2021, 10, 1)));
+    assert_eq!(date.round_to(Period::Year), Ok(Date::new(2022, 1, 1)));
+    assert_eq!(date.round_to(Period::Day), Ok(date.clone()));
+
+    let thursday = Date::new(2021, 7, 22);
+    assert_eq!(thursday.round_down(Period::Week, Weekday::Sunday), Ok(Date::new(2021, 7, 18)));
+    assert_eq!(thursday.round_up(Period::Week, Weekday::Sunday), Ok(Date::new(2021, 7, 25)));
+    assert_eq!(thursday.round_to(Period::Week), Ok(Date::new(2021, 7, 19)));
+    assert_eq!(Date::new(2021, 7, 23).round_to(Period::Week), Ok(Date::new(2021, 7, 26)));
+    assert_eq!(Date::new(2021, 7, 21).round_to(Period::Week), Ok(Date::new(2021, 7, 19)));
+
+    assert_eq!(Date::new(1582, 10, 4).round_up(Period::Month, Weekday::Monday), Ok(Date::new(1582, 11, 1)));
+    assert!(Date::new(2021, 2, 29).round_to(Period::Month).is_err());
+}
+
+#[test]
+fn test_reform_info() {
+    let info = Date::new(1582, 3, 1).reform_info().unwrap();
+    assert_eq!(info.first_skipped, Date::new(1582, 10, 5));
+    assert_eq!(info.last_skipped, Date::new(1582, 10, 14));
+    assert_eq!(info.last_julian, Date::new(1582, 10, 4));
+    assert_eq!(info.first_gregorian, Date::new(1582, 10, 15));
+    assert_eq!(info.skipped_days, 10);
+    assert_eq!(info.last_julian.succ(), Some(info.first_gregorian.clone()));
+    assert!(info.first_skipped.is_valid().is_err());
+    assert!(info.last_skipped.is_valid().is_err());
+
+    assert_eq!(Date::new(1600, 10, 10).reform_info(), None);
+    assert_eq!(Date::new(1581, 10, 10).reform_info(), None);
+}
+
+#[test]
+fn test_eq_tuple() {
+    let date = Date::new(2001, 2, 28);
+    assert_eq!(date, (2001, 2, 28));
+    assert_ne!(date, (2001, 2, 27));
+    assert_ne!(date, (-2001, 2, 28));
+    assert_eq!(Date::new(1582, 10, 4).succ().unwrap(), (1582, 10, 15));
+}
+
+#[test]
+fn test_february_days() {
+    assert_eq!(Date::february_days(2000), 29);
+    assert_eq!(Date::february_days(1900), 28);
+    assert_eq!(Date::february_days(2021), 28);
+    assert_eq!(Date::february_days(2024), 29);
+    assert_eq!(Date::february_days(1500), 29);
+    assert_eq!(Date::february_days(-1), 29);
+}
+
+#[test]
+fn test_leap_years_in() {
+    assert_eq!(Date::leap_years_in(1496, 1508), vec![1496, 1500, 1504, 1508]);
+    assert_eq!(Date::leap_years_in(1580, 1604), vec![1580, 1584, 1588, 1592, 1596, 1600, 1604]);
+    assert_eq!(Date::leap_years_in(1696, 1704), vec![1696, 1704]);
+    assert_eq!(Date::leap_years_in(1892, 1912), vec![1892, 1896, 1904, 1908, 1912]);
+    assert_eq!(Date::leap_years_in(-5, 4), vec![-5, -1, 4]);
+    assert_eq!(Date::leap_years_in(2021, 2023), Vec::<i32>::new());
+    assert_eq!(Date::leap_years_in(2024, 2020), Vec::<i32>::new());
+}
+
+#[test]
+fn test_long_form() {
+    assert_eq!(Date::new(2001, 2, 22).long_form(), Ok("Thursday the 22nd of February, 2001 AD".into()));
+    assert_eq!(Date::new(2021, 7, 1).long_form(), Ok("Thursday the 1st of July, 2021 AD".into()));
+    assert_eq!(Date::new(2021, 7, 13).long_form(), Ok("Tuesday the 13th of July, 2021 AD".into()));
+    assert_eq!(Date::new(2021, 7, 23).long_form(), Ok("Friday the 23rd of July, 2021 AD".into()));
+    assert_eq!(Date::new(-44, 3, 15).long_form(), Ok("Wednesday the 15th of March, 44 BC".into()));
+    assert!(Date::new(2021, 2, 29).long_form().is_err());
+}
+
+#[test]
+fn test_add_days_i64() {
+    let date = Date::new(2000, 1, 1);
+    assert_eq!(date.add_days_i64(366), Ok(Date::new(2001, 1, 1)));
+    assert_eq!(date.add_days_i64(-16_000), date.add_days(-16_000));
+    assert_eq!(date.add_days_i64(146_097 * 125_000), Ok(Date::new(50_002_000, 1, 1)));
+    assert_eq!(date.add_days_i64(50_000_000).map(|date| date.year), Ok(138_895));
+    assert_eq!(Date::MAX.add_days_i64(0), Ok(Date::MAX));
+    assert!(Date::MAX.add_days_i64(1).is_err());
+    assert!(Date::MIN.add_days_i64(-1).is_err());
+    assert!(date.add_days_i64(i64::MAX).is_err());
+    assert!(Date::new(2021, 2, 29).add_days_i64(1).is_err());
+}
+
+#[test]
+fn test_parse_julian_day() {
+    assert_eq!(Date::parse_julian_day("2451545"), Ok(Date::new(2000, 1, 1)));
+    assert_eq!(Date::parse_julian_day("2299161"), Ok(Date::new(1582, 10, 15)));
+    assert_eq!(Date::parse_julian_day("0"), Ok(Date::new(-4713, 1, 1)));
+    assert_eq!(Date::parse_julian_day("-1"), Ok(Date::new(-4714, 12, 31)));
+
+    assert_eq!(Date::parse_julian_day("abc").map_err(|error| error.message), Err("abc: Invalid Julian day".into()));
+    assert!(Date::parse_julian_day("").is_err());
+    assert!(Date::parse_julian_day("2451545.5").is_err());
+    assert!(Date::parse_julian_day("99999999999999999999").is_err());
+    assert!(Date::parse_julian_day(&i64::MAX.to_string()).is_err());
+}
+
+#[test]
+fn test_jan1_weekday() {
+    //walk the year lengths from January 1, 1970, a Thursday
+    let mut weekday = Weekday::Thursday.days_from_monday();
+    for year in 1970..=2500 {
+        assert_eq!(Date::jan1_weekday(year), Weekday::from_monday(weekday), "{}", year);
+        weekday += Date::year_days(year);
+    }
+    let mut weekday = Weekday::Thursday.days_from_monday();
+    for year in (1500..1970).rev() {
+        weekday -= Date::year_days(year);
+        assert_eq!(Date::jan1_weekday(year), Weekday::from_monday(weekday), "{}", year);
+    }
+    assert_eq!(Date::jan1_weekday(1), Weekday::Saturday);
+    assert_eq!(Date::jan1_weekday(-1), Weekday::Thursday);
+    assert_eq!(Date::jan1_weekday(i32::MAX), Weekday::Tuesday);
+    assert_eq!(Date::new(i32::MAX, 12, 27).iso_week(), Ok((i32::MAX, 52)));
+    assert!(Date::MAX.iso_week().is_err());
+    assert!(Date::MIN.iso_week().is_err());
+    assert!(Date::MIN.period_key(Period::Week).is_err());
+    assert_eq!(Date::new(i32::MIN, 1, 31).iso_week().map(|(year, _)| year), Ok(i32::MIN));
+}
+
+#[test]
+fn test_october_1582_messages() {
+    assert_eq!(Date::new(1582, 10, 4).is_valid(), Ok(()));
+    assert_eq!(Date::new(1582, 10, 5).is_valid(), Err("October 5, 1582 does not exist".into()));
+    assert_eq!(Date::new(1582, 10, 14).is_valid(), Err("October 14, 1582 does not exist".into()));
+    assert_eq!(Date::new(1582, 10, 15).is_valid(), Ok(()));
+    assert_eq!(Date::new(1582, 10, 31).is_valid(), Ok(()));
+    assert_eq!(Date::new(1582, 10, 32).is_valid(), Err("October 32, 1582: Invalid day".into()));
+    assert_eq!(Date::new(1582, 10, 40).is_valid(), Err("October 40, 1582: Invalid day".into()));
+    assert_eq!(Date::new(1582, 10, 0).is_valid(), Err("October 0, 1582: Invalid day".into()));
+}
+
+#[test]
+fn test_duration_in() {
+    let first = Date::new(2020, 2, 29);
+    let last = Date::new(2021, 7, 22);
+    assert_eq!(Date::duration_in(&first, &last, DurationUnit::Days), Ok(509));
+    assert_eq!(Date::duration_in(&first, &last, DurationUnit::Weeks), Ok(72));
+    assert_eq!(Date::duration_in(&first, &last, DurationUnit::Months), Ok(16));
+    assert_eq!(Date::duration_in(&first, &last, DurationUnit::Years), Ok(1));
+
+    assert_eq!(Date::duration_in(&last, &first, DurationUnit::Days), Ok(-509));
+    assert_eq!(Date::duration_in(&last, &first, DurationUnit::Weeks), Ok(-72));
+    assert_eq!(Date::duration_in(&last, &first, DurationUnit::Months), Ok(-16));
+    assert_eq!(Date::duration_in(&last, &first, DurationUnit::Years), Ok(-1));
+
+    let jan31 = Date::new(2021, 1, 31);
+    assert_eq!(Date::duration_in(&jan31, &Date::new(2021, 2, 28), DurationUnit::Months), Ok(0));
+    assert_eq!(Date::duration_in(&jan31, &Date::new(2021, 3, 31), DurationUnit::Months), Ok(2));
+    assert_eq!(Date::duration_in(&Date::new(-1, 7, 1), &Date::new(1, 7, 1), DurationUnit::Years), Ok(1));
+    assert!(Date::duration_in(&jan31, &Date::new(2021, 2, 29), DurationUnit::Days).is_err());
+}
+
+#[test]
+fn test_wrap_day_of_year() {
+    assert_eq!(Date::wrap_day_of_year(2021, 1), Ok(Date::new(2021, 1, 1)));
+    assert_eq!(Date::wrap_day_of_year(2021, 365), Ok(Date::new(2021, 12, 31)));
+    assert_eq!(Date::wrap_day_of_year(2021, 366), Ok(Date::new(2021, 1, 1)));
+    assert_eq!(Date::wrap_day_of_year(2021, 367), Ok(Date::new(2021, 1, 2)));
+    assert_eq!(Date::wrap_day_of_year(2021, 0), Ok(Date::new(2021, 12, 31)));
+    assert_eq!(Date::wrap_day_of_year(2021, -364), Ok(Date::new(2021, 1, 1)));
+    assert_eq!(Date::wrap_day_of_year(2020, 366), Ok(Date::new(2020, 12, 31)));
+    assert_eq!(Date::wrap_day_of_year(2020, 367), Ok(Date::new(2020, 1, 1)));
+
+    assert_eq!(Date::wrap_day_of_year(1582, 278), Ok(Date::new(1582, 10, 15)));
+    assert_eq!(Date::wrap_day_of_year(1582, 356), Ok(Date::new(1582, 1, 1)));
+    assert_eq!(Date::wrap_day_of_year(1582, -77), Ok(Date::new(1582, 10, 15)));
+    assert!(Date::wrap_day_of_year(0, 1).is_err());
+    assert_eq!(Date::wrap_day_of_year(2021, i32::MIN), Ok(Date::new(2021, 2, 26)));
+    assert_eq!(Date::wrap_day_of_year(2021, i32::MAX), Ok(Date::new(2021, 11, 3)));
+}
+
+#[test]
+fn test_parse() {
+    let date = Date::new(2021, 7, 22);
+    assert_eq!(Date::parse("2021-07-22"), Ok(date.clone()));
+    assert_eq!(Date::parse("20210722"), Ok(date.clone()));
+    assert_eq!(Date::parse("07/22/21"), Ok(date.clone()));
+    assert_eq!(Date::parse("July 22, 2021"), Ok(date.clone()));
+    assert_eq!(Date::parse(&date.to_string()), Ok(date));
+
+    assert_eq!(Date::parse("-0044-03-15"), Ok(Date::new(-44, 3, 15)));
+    assert_eq!(Date::parse("-00440315"), Ok(Date::new(-44, 3, 15)));
+    assert_eq!(Date::parse("March 15, 44 BC"), Ok(Date::new(-44, 3, 15)));
+    assert_eq!(Date::parse("july 4, 1776"), Ok(Date::new(1776, 7, 4)));
+
+    assert_eq!(Date::parse("garbage!").map_err(|error| error.message), Err("garbage!: Unrecognised date, expected YYYY-MM-DD, \
+                                            YYYYMMDD, MM/DD/YY or Month D, YYYY".into()));
+    assert!(Date::parse("").is_err());
+    assert!(Date::parse("2021-7-22").is_err());
+    assert!(Date::parse("2021-02-29").is_err());
+    assert!(Date::parse("Julember 22, 2021").is_err());
+    assert!(Date::parse("July 22 2021").is_err());
+    assert!(Date::parse("October 10, 1582").is_err());
+}
+
+#[test]
+fn test_bc_leap_years() {
+    assert!(Date::is_leap(-1));
+    assert!(!Date::is_leap(-4));
+    assert!(Date::is_leap(-5));
+    assert!(!Date::is_leap(-8));
+    assert!(Date::is_leap(-9));
+    assert_eq!(Date::new(-5, 12, 31).day_of_year(), 366);
+
+    assert!(Date::is_historical_leap(-42));
+    assert!(Date::is_historical_leap(-9));
+    assert!(!Date::is_historical_leap(-5));
+    assert!(!Date::is_historical_leap(-1));
+    assert!(!Date::is_historical_leap(4));
+    assert!(Date::is_historical_leap(8));
+    assert!(Date::is_historical_leap(-49));
+    assert!(Date::is_historical_leap(2000));
+}
+
+#[test]
+fn test_day_of_year_newtype() {
+    let day = DayOfYear::new(2021, 203).unwrap();
+    assert_eq!(day.get(), 203);
+    assert_eq!(day.to_date(2021), Ok(Date::new(2021, 7, 22)));
+    assert_eq!(DayOfYear::new(2020, 366).and_then(|day| day.to_date(2020)), Ok(Date::new(2020, 12, 31)));
+    assert_eq!(DayOfYear::new(1582, 278).and_then(|day| day.to_date(1582)), Ok(Date::new(1582, 10, 15)));
+    for date in &[Date::new(2021, 1, 1), Date::new(2000, 2, 29), Date::new(1582, 10, 4), Date::new(-44, 3, 15)] {
+        let day = DayOfYear::new(date.year, date.day_of_year() as u16).unwrap();
+        assert_eq!(day.to_date(date.year), Ok(date.clone()));
+    }
+
+    assert!(DayOfYear::new(2021, 0).is_err());
+    assert!(DayOfYear::new(2021, 366).is_err());
+    assert!(DayOfYear::new(1582, 356).is_err());
+    assert!(DayOfYear::new(0, 1).is_err());
+    assert!(DayOfYear::new(2020, 366).unwrap().to_date(2021).is_err());
+}
+
+#[test]
+fn test_next_business_day() {
+    assert_eq!(Date::new(2021, 7, 23).next_business_day(), Ok(Date::new(2021, 7, 26)));
+    assert_eq!(Date::new(2021, 7, 24).next_business_day(), Ok(Date::new(2021, 7, 26)));
+    assert_eq!(Date::new(2021, 7, 25).next_business_day(), Ok(Date::new(2021, 7, 26)));
+    assert_eq!(Date::new(2021, 7, 21).next_business_day(), Ok(Date::new(2021, 7, 22)));
+    assert_eq!(Date::new(2021, 12, 31).next_business_day(), Ok(Date::new(2022, 1, 3)));
+    assert_eq!(Date::new(1582, 10, 4).next_business_day(), Ok(Date::new(1582, 10, 15)));
+    assert!(Date::new(2021, 2, 29).next_business_day().is_err());
+}
+
+#[test]
+fn test_age_in_days() {
+    let birth = Date::new(1977, 10, 1);
+    assert_eq!(Date::age_in_days(&birth, &Date::new(2021, 7, 22)), Ok(16000));
+    assert_eq!(Date::age_in_days(&birth, &birth), Ok(0));
+    assert_eq!(Date::age_in_days(&Date::new(2021, 7, 22), &birth),
+               Err("October 1, 1977 is before the birth date July 22, 2021".into()));
+    assert!(Date::age_in_days(&birth, &Date::new(2021, 2, 29)).is_err());
+    assert!(Date::age_in_days(&birth, &Date::new(9_000_000, 1, 1)).is_err());
+}
+
+#[cfg(feature = "clock")]
+#[test]
+fn test_from_system_time() {
+    use std::time::{Duration, UNIX_EPOCH};
+    assert_eq!(Date::from_system_time(UNIX_EPOCH), Ok(Date::new(1970, 1, 1)));
+    assert_eq!(Date::from_system_time(UNIX_EPOCH + Duration::from_secs(1_626_955_200)),
+               Ok(Date::new(2021, 7, 22)));
+    assert_eq!(Date::from_system_time(UNIX_EPOCH - Duration::from_millis(1)), Ok(Date::new(1969, 12, 31)));
+    assert_eq!(Date::from_system_time(UNIX_EPOCH - Duration::from_secs(86_400)), Ok(Date::new(1969, 12, 31)));
+    assert_eq!(Date::from_system_time(UNIX_EPOCH - Duration::from_millis(86_400_001)),
+               Ok(Date::new(1969, 12, 30)));
 }
 
+#[cfg(feature = "step")]
 #[test]
-fn test_days_in_year() {
-    assert_eq!(Date::year_days(1977),365);
-    assert_eq!(Date::year_days(1978),365);
-    assert_eq!(Date::year_days(1980),366);
-    assert_eq!(Date::year_days(2000),366);
-    assert_eq!(Date::year_days(2100),365);
-    assert_eq!(Date::year_days(1582),355);
-    assert_eq!(Date::year_days(0),0);
-    assert_eq!(Date::year_days(-1),366);
+fn test_step() {
+    let days: Vec<Date> = (Date::new(2021, 7, 22)..Date::new(2021, 8, 2)).collect();
+    assert_eq!(days.len(), 11);
+    assert_eq!(days[10], Date::new(2021, 8, 1));
+    assert_eq!((Date::new(1582, 10, 1)..=Date::new(1582, 10, 20)).count(), 10);
+    assert_eq!((Date::new(1582, 10, 1)..Date::new(1582, 10, 20)).nth(4), Some(Date::new(1582, 10, 15)));
+    assert_eq!((Date::new(2020, 1, 1)..Date::new(2021, 1, 1)).next_back(), Some(Date::new(2020, 12, 31)));
+    assert_eq!((Date::new(2021, 1, 1)..Date::new(2020, 1, 1)).count(), 0);
+    assert_eq!(std::iter::Step::forward_checked(Date::MAX, 1), None);
+    assert_eq!(std::iter::Step::backward_checked(Date::new(1582, 10, 15), 1), Some(Date::new(1582, 10, 4)));
 }
 
+#[cfg(feature = "time")]
 #[test]
-fn test_days_between_dates() {
-    let first = Date::new(1950, 1, 1);
-    let last = Date::new(1950, 12, 31);
-    assert_eq!(Date::days_between_dates(&first, &last), Ok(364));
+fn test_time_date() {
+    let date = Date::new(2021, 7, 22);
+    let converted = date.to_time_date().unwrap();
+    assert_eq!((converted.year(), converted.month(), converted.day()), (2021, time::Month::July, 22));
+    assert_eq!(Date::from_time_date(converted), Some(date));
+    let reform = Date::gregorian_reform_date().to_time_date().unwrap();
+    assert_eq!(Date::from_time_date(reform), Some(Date::gregorian_reform_date()));
 
-    let first = Date::new(1950, 1, 1);
-    let last = Date::new(1950, 1, 2);
-    assert_eq!(Date::days_between_dates(&first, &last), Ok(1));
+    assert_eq!(Date::julian_end_date().to_time_date(), None);
+    assert_eq!(Date::new(-44, 3, 15).to_time_date(), None);
+    assert_eq!(Date::new(2021, 2, 29).to_time_date(), None);
+    assert_eq!(Date::new(10_000, 1, 1).to_time_date(), None);
+    assert_eq!(Date::from_time_date(reform.previous_day().unwrap()), None);
+}
 
-    assert_eq!(Date::days_between_dates(&first, &first), Ok(0));
+#[test]
+fn test_quarters_in() {
+    let quarters: Vec<(i32, u8)> = Date::quarters_in(Date::new(2020, 11, 15), Date::new(2021, 7, 2)).collect();
+    assert_eq!(quarters, vec![(2020, 4), (2021, 1), (2021, 2), (2021, 3)]);
+    assert_eq!(Date::quarters_in(Date::new(2018, 1, 1), Date::new(2022, 1, 1)).count(), 16);
+    assert_eq!(Date::quarters_in(Date::new(2018, 1, 1), Date::new(2022, 1, 2)).count(), 17);
+    assert_eq!(Date::quarters_in(Date::new(2021, 3, 31), Date::new(2021, 4, 1)).collect::<Vec<_>>(), vec![(2021, 1)]);
+    assert_eq!(Date::quarters_in(Date::new(-1, 12, 1), Date::new(1, 2, 1)).collect::<Vec<_>>(), vec![(-1, 4), (1, 1)]);
+    assert_eq!(Date::quarters_in(Date::new(2021, 4, 1), Date::new(2021, 4, 1)).count(), 0);
+    assert_eq!(Date::quarters_in(Date::new(i32::MAX, 11, 1), Date::MAX).count(), 1);
+    assert_eq!(Date::quarters_in(Date::new(2021, 0, 1), Date::new(2022, 1, 1)).count(), 0);
 
-    let first = Date::new(1980, 1, 1);
-    let last = Date::new(1981, 1, 1);
-    assert_eq!(Date::days_between_dates(&first, &last), Ok(366));
-    assert_eq!(Date::days_between_dates(&last, &first), Ok(-366));
+    assert_eq!(Date::new(2021, 3, 31).quarter(), Some(1));
+    assert_eq!(Date::new(2021, 12, 1).quarter(), Some(4));
+    assert_eq!(Date::new(2021, 0, 1).quarter(), None);
+    assert_eq!(Date::new(2021, 13, 1).quarter(), None);
+}
+
+#[test]
+fn test_checked_from_ymd() {
+    assert_eq!(Date::checked_from_ymd(2021, 7, 22), Some(Date::new(2021, 7, 22)));
+    assert_eq!(Date::checked_from_ymd(2020, 2, 29), Some(Date::new(2020, 2, 29)));
+    assert_eq!(Date::checked_from_ymd(2020, 2, 30), None);
+    assert_eq!(Date::checked_from_ymd(0, 1, 1), None);
+    assert_eq!(Date::checked_from_ymd(2021, 13, 1), None);
+    assert_eq!(Date::checked_from_ymd(1582, 10, 10), None);
+}
+
+#[test]
+fn test_reform_dates() {
+    assert_eq!(Date::gregorian_reform_date(), (1582, 10, 15));
+    assert_eq!(Date::julian_end_date(), (1582, 10, 4));
+    assert!(Date::gregorian_reform_date().is_valid().is_ok());
+    assert!(Date::julian_end_date().is_valid().is_ok());
+    assert_eq!(Date::gregorian_reform_date().pred(), Some(Date::julian_end_date()));
+    assert_eq!(Date::gregorian_reform_date().to_julian_day(), Ok(GREGORIAN_JULIAN_DAY));
+}
+
+#[test]
+fn test_tuples() {
+    let date = Date::new(2021, 7, 22);
+    assert_eq!(date.to_tuple(), (2021, 7, 22));
+    assert_eq!(Date::try_from_tuple(date.to_tuple()), Ok(date.clone()));
+    let tuple: (i32, u8, u8) = date.clone().into();
+    assert_eq!(Date::try_from(tuple), Ok(date));
 
+    for &tuple in &[(-44, 3, 15), (1582, 10, 4), (1582, 10, 15), (2000, 2, 29)] {
+        assert_eq!(Date::try_from(tuple).map(<(i32, u8, u8)>::from), Ok(tuple));
+    }
+    assert_eq!(Date::try_from((2021, 2, 29)), Err(DateError::InvalidDay(Date::new(2021, 2, 29))));
+    assert_eq!(Date::try_from_tuple((0, 1, 1)), Err(DateError::YearZero));
+}
+
+#[test]
+fn test_days_apart() {
     let first = Date::new(1977, 10, 1);
     let last = Date::new(2021, 7, 22);
-    assert_eq!(Date::days_between_dates(&first, &last), Ok(16000));
+    assert_eq!(Date::days_apart(&first, &last), Ok(16000));
+    assert_eq!(Date::days_apart(&last, &first), Ok(16000));
+    assert_eq!(Date::days_apart(&first, &first), Ok(0));
+    assert_eq!(Date::days_apart(&Date::new(1582, 10, 15), &Date::new(1582, 10, 4)), Ok(1));
+    assert!(Date::days_apart(&first, &Date::new(0, 1, 1)).is_err());
+    let far = Date::new(9_000_000, 1, 1);
+    assert!(Date::days_apart(&first, &far).unwrap() > i32::MAX as i64);
+    assert_eq!(Date::days_apart(&first, &far), Date::days_apart(&far, &first));
+    assert_eq!(Date::days_apart(&Date::MIN, &Date::MAX), Ok(Date::MAX.to_rata_die() - Date::MIN.to_rata_die()));
+}
 
+#[test]
+fn test_leap_day() {
+    assert!(Date::has_leap_day(2020));
+    assert!(!Date::has_leap_day(2021));
+    assert!(!Date::has_leap_day(0));
+    assert_eq!(Date::leap_day(2020), Some(Date::new(2020, 2, 29)));
+    assert_eq!(Date::leap_day(1500), Some(Date::new(1500, 2, 29)));
+    assert_eq!(Date::leap_day(1900), None);
+    assert_eq!(Date::leap_day(2021), None);
+    assert_eq!(Date::leap_day(0), None);
+    assert!(Date::leap_day(-1).unwrap().is_valid().is_ok());
+}
 
-    let first = Date::new(2000, 1, 1);
-    let last = Date::new(2400, 1, 1);
-    assert_eq!(Date::days_between_dates(&first, &last), Ok(146097));
+#[test]
+fn test_serial() {
+    let dates = [Date::MIN, Date::new(-44, 3, 15), Date::new(-1, 12, 31), Date::new(1, 1, 1),
+                 Date::new(1582, 10, 4), Date::new(1582, 10, 15), Date::new(2021, 7, 22), Date::MAX];
+    for date in &dates {
+        assert_eq!(Date::from_serial(date.to_serial()), Ok(date.clone()));
+    }
+    for pair in dates.windows(2) {
+        assert!(pair[0] < pair[1]);
+        assert!(pair[0].to_serial() < pair[1].to_serial());
+    }
+    assert!(Date::from_serial(i64::MIN).is_err());
+}
 
-    let first = Date::new(2000, 1, 1);
-    let last = Date::new(2400, 1, 1);
-    assert_eq!(Date::days_between_dates(&first, &last), Ok(146097));
+#[test]
+fn test_weekday_name() {
+    let date = Date::new(2021, 7, 22);
+    assert_eq!(date.weekday_name(), Ok("Thursday"));
+    assert_eq!(date.weekday_short_name(), Ok("Thu"));
+    assert_eq!(Date::new(2021, 7, 25).weekday_name(), Ok("Sunday"));
+    assert_eq!(Date::new(2021, 7, 19).weekday_short_name(), Ok("Mon"));
+    assert_eq!(Weekday::Wednesday.name(), "Wednesday");
+    assert!(Date::new(2021, 2, 29).weekday_name().is_err());
+}
 
-    let first = Date::new(1582, 1, 1);
-    let last = Date::new(1982, 1, 1);
-    assert_eq!(Date::days_between_dates(&first, &last), Ok(146087));
+#[test]
+fn test_display_padded() {
+    assert_eq!(Date::new(2001, 2, 3).display_padded(), "February 3, 2001");
+    assert_eq!(Date::new(5, 2, 3).display_padded(), "February 3, 0005");
+    assert_eq!(Date::new(-5, 2, 3).display_padded(), "February 3, 0005 BC");
+    assert_eq!(Date::new(-753, 4, 21).display_padded(), "April 21, 0753 BC");
+    assert_eq!(Date::new(12345, 2, 3).display_padded(), "February 3, 12345");
+    assert_eq!(Date::MIN.display_padded(), "January 1, 2147483648 BC");
+}
 
-    let first = Date::new(0, 1, 1);
-    assert!(Date::days_between_dates(&first, &last).is_err());
+#[test]
+fn test_century_leap_days() {
+    for &year in &[1700, 1800, 1900, 2100] {
+        assert_eq!(Date::new(year, 2, 29).is_valid(), Err(format!("February 29, {}: Invalid day", year)));
+    }
+    for &year in &[1600, 2000, 2400, 1500, 1300, 1100, -101] {
+        assert_eq!(Date::new(year, 2, 29).is_valid(), Ok(()));
+    }
+}
+
+#[test]
+fn test_era() {
+    assert_eq!(Date::new(-44, 3, 15).era(), Era::Bc);
+    assert_eq!(Date::new(-1, 12, 31).era(), Era::Bc);
+    assert_eq!(Date::new(1, 1, 1).era(), Era::Ad);
+    assert_eq!(Date::new(2021, 7, 22).era(), Era::Ad);
+}
+
+#[test]
+fn test_days_in_decade_and_century() {
+    assert_eq!(Date::days_in_decade(1580), Ok(3643));
+    assert_eq!(Date::days_in_decade(1580), Ok((1580..1590).map(Date::year_days).sum()));
+    assert_eq!(Date::days_in_decade(2011), Ok(3653));
+    assert_eq!(Date::days_in_decade(-5), Ok((-5..=5).map(Date::year_days).sum()));
+    assert_eq!(Date::days_in_century(1901), Ok(36_525));
+    assert_eq!(Date::days_in_century(1900), Ok(36_524));
+    assert_eq!(Date::days_in_century(1500), Ok((1500..1600).map(Date::year_days).sum()));
+    assert!(Date::days_in_decade(0).is_err());
+    assert!(Date::days_in_century(i32::MAX - 50).is_err());
+}
+
+#[test]
+fn test_format_and_parse_exact() {
+    let date = Date::new(2021, 7, 22);
+    assert_eq!(date.format("%Y-%m-%d"), Ok("2021-07-22".into()));
+    assert_eq!(date.format("%A, %B %d, %Y"), Ok("Thursday, July 22, 2021".into()));
+    assert_eq!(date.format("%a %d %b %Y 100%%"), Ok("Thu 22 Jul 2021 100%".into()));
+    assert!(date.format("%Q").is_err());
+    assert!(Date::new(2021, 2, 29).format("%Y").is_err());
+
+    let patterns = ["%Y-%m-%d", "%Y%m%d", "%d/%m/%Y", "%A, %B %d, %Y", "%a %d %b %Y 100%%", "%m.%d.%Y"];
+    for date in &[date, Date::new(-44, 3, 15), Date::new(1582, 10, 15), Date::new(2000, 2, 29),
+                  Date::new(12345, 5, 1)] {
+        for pattern in &patterns {
+            assert_eq!(Date::parse_exact(&date.format(pattern).unwrap(), pattern), Ok(date.clone()));
+        }
+    }
+
+    assert_eq!(Date::parse_exact("2021-07-22", "%Y/%m/%d").map_err(|error| error.message),
+               Err("2021-07-22: Does not match the pattern %Y/%m/%d".into()));
+    assert!(Date::parse_exact("2021-07-22x", "%Y-%m-%d").is_err());
+    assert!(Date::parse_exact("2021-7-22", "%Y-%m-%d").is_err());
+    assert!(Date::parse_exact("Jly 22 2021", "%b %d %Y").is_err());
+    assert!(Date::parse_exact("2021-02-29", "%Y-%m-%d").is_err());
+    assert!(Date::parse_exact("Friday 2021-07-22", "%A %Y-%m-%d").is_err());
+    assert!(Date::parse_exact("2021-07", "%Y-%m").is_err());
+}
+
+#[test]
+fn test_is_before_after_between() {
+    let bc = Date::new(-44, 3, 15);
+    let ad = Date::new(1, 1, 1);
+    assert!(bc.is_before(&ad));
+    assert!(ad.is_after(&bc));
+    assert!(Date::new(-44, 3, 15).is_after(&Date::new(-45, 12, 31)));
+    assert!(!bc.is_before(&bc));
+    assert!(!bc.is_after(&bc));
+
+    let start = Date::new(2021, 7, 1);
+    let end = Date::new(2021, 7, 31);
+    assert!(start.is_between(&start, &end));
+    assert!(end.is_between(&start, &end));
+    assert!(Date::new(2021, 7, 22).is_between(&start, &end));
+    assert!(!Date::new(2021, 8, 1).is_between(&start, &end));
+    assert!(!Date::new(2021, 7, 22).is_between(&end, &start));
+    assert!(Date::new(-1, 12, 31).is_between(&bc, &ad));
+}
+
+#[test]
+fn test_epoch_weekday() {
+    assert_eq!(Date::epoch_weekday(), Weekday::Thursday);
+    assert_eq!(Date::default().weekday(), Ok(Date::epoch_weekday()));
+    assert_eq!(Date::new(1970, 1, 5).weekday(), Ok(Weekday::Monday));
+    assert_eq!(Date::jan1_weekday(1970), Date::epoch_weekday());
+    assert_eq!(Date::new(1970, 1, 1).julian_day_number(), UNIX_EPOCH_JULIAN_DAY);
+}
+
+#[test]
+fn test_checked_sub() {
+    let first = Date::new(2019, 5, 20);
+    let last = Date::new(2021, 7, 22);
+    let forward = DateDelta { total_days: 794, years: 2, months: 2, days: 2 };
+    assert_eq!(last.checked_sub(&first), Ok(forward));
+    assert_eq!(first.checked_sub(&last), Ok(DateDelta { total_days: -794, years: -2, months: -2, days: -2 }));
+    assert_eq!(last.checked_sub(&last), Ok(DateDelta { total_days: 0, years: 0, months: 0, days: 0 }));
+
+    assert_eq!(Date::new(2021, 3, 1).checked_sub(&Date::new(2021, 1, 31)),
+               Ok(DateDelta { total_days: 29, years: 0, months: 1, days: 1 }));
+    assert_eq!(Date::new(2021, 7, 10).checked_sub(&Date::new(2020, 7, 22)),
+               Ok(DateDelta { total_days: 353, years: 0, months: 11, days: 18 }));
+    assert_eq!(Date::new(1, 3, 1).checked_sub(&Date::new(-1, 3, 1)),
+               Ok(DateDelta { total_days: 365, years: 1, months: 0, days: 0 }));
+    assert_eq!(Date::new(1582, 10, 20).checked_sub(&Date::new(1582, 9, 10)),
+               Ok(DateDelta { total_days: 30, years: 0, months: 1, days: 5 }));
+    assert!(last.checked_sub(&Date::new(2021, 2, 29)).is_err());
+}
+
+#[test]
+fn test_range_with_mode() {
+    let start = Date::new(1582, 10, 3);
+    let end = Date::new(1582, 10, 17);
+    let skipped: Vec<RangeItem> = Date::range_with_mode(start.clone(), end.clone(), RangeMode::default()).collect();
+    assert_eq!(skipped, vec![RangeItem::Day(Date::new(1582, 10, 3)), RangeItem::Day(Date::new(1582, 10, 4)),
+                             RangeItem::Day(Date::new(1582, 10, 15)), RangeItem::Day(Date::new(1582, 10, 16))]);
+
+    let gap: Vec<RangeItem> = Date::range_with_mode(start, end.clone(), RangeMode::Gap).collect();
+    assert_eq!(gap, vec![RangeItem::Day(Date::new(1582, 10, 3)), RangeItem::Day(Date::new(1582, 10, 4)),
+                         RangeItem::Gap { first: Date::new(1582, 10, 5), last: Date::new(1582, 10, 14) },
+                         RangeItem::Day(Date::new(1582, 10, 15)), RangeItem::Day(Date::new(1582, 10, 16))]);
+
+    assert_eq!(Date::range_with_mode(Date::new(1582, 10, 15), end, RangeMode::Gap).count(), 2);
+    assert_eq!(Date::range_with_mode(Date::new(1582, 10, 1), Date::new(1582, 10, 4), RangeMode::Gap).count(), 3);
+    assert_eq!(Date::range_with_mode(Date::new(2021, 1, 1), Date::new(2022, 1, 1), RangeMode::Gap).count(), 365);
+}
+
+#[test]
+fn test_locale_fallback() {
+    let mut months = [None; 12];
+    months[0] = Some("enero");
+    months[6] = Some("julio");
+    let spanish = Locale::new(months);
+    assert_eq!(Date::new(2021, 1, 1).localized_month_name(&spanish), Some("enero"));
+    assert_eq!(Date::new(2021, 7, 22).localized_month_name(&spanish), Some("julio"));
+    assert_eq!(Date::new(2021, 8, 1).localized_month_name(&spanish), Some("August"));
+    assert_eq!(Date::new(2021, 13, 1).localized_month_name(&spanish), None);
+
+    let mut months = [None; 12];
+    months[0] = Some("xaneiro");
+    months[1] = Some("febreiro");
+    let galician = Locale::new(months).with_fallback(spanish);
+    assert_eq!(galician.month_name(1), Some("xaneiro"));
+    assert_eq!(galician.month_name(2), Some("febreiro"));
+    assert_eq!(galician.month_name(7), Some("julio"));
+    assert_eq!(galician.month_name(12), Some("December"));
+    assert_eq!(galician.month_name(0), None);
+}
+
+#[test]
+fn test_simple_weeks() {
+    let date = Date::new(2021, 1, 3);
+    assert_eq!(date.week_of_year(Weekday::Monday), Ok(1));
+    assert_eq!(date.week_of_year(Weekday::Sunday), Ok(2));
+    assert_eq!(Date::new(2021, 1, 4).week_of_year(Weekday::Monday), Ok(2));
+    assert_eq!(Date::new(2021, 12, 31).week_of_year(Weekday::Monday), Ok(53));
+    assert_eq!(Date::from_simple_week(2021, 1, Weekday::Friday, Weekday::Monday), Ok(Date::new(2021, 1, 1)));
+    assert_eq!(Date::from_simple_week(2021, 2, Weekday::Sunday, Weekday::Sunday), Ok(Date::new(2021, 1, 3)));
+
+    for &week_start in &[Weekday::Monday, Weekday::Sunday] {
+        for &year in &[2021, 2020, 1582] {
+            for date in Date::calendar_year(year).unwrap() {
+                let week = date.week_of_year(week_start).unwrap();
+                let weekday = date.weekday().unwrap();
+                assert_eq!(Date::from_simple_week(year, week, weekday, week_start), Ok(date));
+            }
+        }
+    }
+
+    assert!(Date::from_simple_week(2021, 1, Weekday::Thursday, Weekday::Monday).is_err());
+    assert!(Date::from_simple_week(2021, 53, Weekday::Saturday, Weekday::Monday).is_err());
+    assert!(Date::from_simple_week(2021, 0, Weekday::Monday, Weekday::Monday).is_err());
+    assert!(Date::from_simple_week(2021, 60, Weekday::Monday, Weekday::Monday).is_err());
+}
+
+#[test]
+fn test_compare_absolute() {
+    use CalendarSystem::{Hybrid, ProlepticGregorian};
+    let julian = Date::new(1582, 10, 4);
+    let gregorian = Date::new(1582, 10, 14);
+    assert!(julian < gregorian);
+    assert_eq!(julian.compare_absolute(&gregorian, Hybrid, ProlepticGregorian), Ordering::Equal);
+    assert_eq!(gregorian.compare_absolute(&julian, ProlepticGregorian, Hybrid), Ordering::Equal);
+    assert_eq!(julian.compare_absolute(&gregorian, Hybrid, Hybrid), Ordering::Less);
+    assert_eq!(Date::new(1, 1, 1).compare_absolute(&Date::new(-1, 12, 30), Hybrid, ProlepticGregorian),
+               Ordering::Equal);
+    assert_eq!(Date::new(1000, 1, 1).compare_absolute(&Date::new(1000, 1, 1), Hybrid, ProlepticGregorian),
+               Ordering::Greater);
+
+    let modern = Date::new(2021, 7, 22);
+    assert_eq!(modern.compare_absolute(&modern, Hybrid, ProlepticGregorian), Ordering::Equal);
+    assert_eq!(modern.compare_absolute(&Date::new(2021, 7, 23), ProlepticGregorian, Hybrid), Ordering::Less);
+}
+
+#[test]
+fn test_next_month_same_day() {
+    let date = Date::new(2021, 1, 31);
+    assert_eq!(date.next_month_same_day(DayPolicy::Clamp), Ok(Date::new(2021, 2, 28)));
+    assert_eq!(date.next_month_same_day(DayPolicy::Skip), Err("February 2021 has no day 31".into()));
+    assert_eq!(date.next_month_same_day(DayPolicy::Overflow), Ok(Date::new(2021, 3, 3)));
+    assert_eq!(Date::new(2020, 1, 31).next_month_same_day(DayPolicy::Overflow), Ok(Date::new(2020, 3, 2)));
+
+    for &policy in &[DayPolicy::Clamp, DayPolicy::Skip, DayPolicy::Overflow] {
+        assert_eq!(Date::new(2021, 1, 15).next_month_same_day(policy), Ok(Date::new(2021, 2, 15)));
+        assert_eq!(Date::new(2021, 12, 31).next_month_same_day(policy), Ok(Date::new(2022, 1, 31)));
+        assert_eq!(Date::new(-1, 12, 1).next_month_same_day(policy), Ok(Date::new(1, 1, 1)));
+    }
+
+    assert_eq!(Date::new(1582, 9, 10).next_month_same_day(DayPolicy::Clamp), Ok(Date::new(1582, 10, 15)));
+    assert!(Date::new(1582, 9, 10).next_month_same_day(DayPolicy::Skip).is_err());
+    assert!(Date::MAX.next_month_same_day(DayPolicy::Clamp).is_err());
+    assert!(Date::new(2021, 2, 29).next_month_same_day(DayPolicy::Clamp).is_err());
+}
+
+#[test]
+fn test_rfc3339_date() {
+    assert_eq!(Date::new(2021, 7, 22).rfc3339_date(), Ok("2021-07-22".into()));
+    assert_eq!(Date::new(33, 1, 5).rfc3339_date(), Ok("0033-01-05".into()));
+    assert_eq!(Date::new(9999, 12, 31).rfc3339_date(), Ok("9999-12-31".into()));
+    assert_eq!(Date::new(-44, 3, 15).rfc3339_date(), Err("March 15, 44 BC: RFC 3339 only allows years 1 to 9999".into()));
+    assert!(Date::new(10000, 1, 1).rfc3339_date().is_err());
+    assert!(Date::new(2021, 2, 29).rfc3339_date().is_err());
+}
+
+/// Check the round trips of every valid date from *first_year* to *last_year*, both included
+#[cfg(test)]
+fn assert_self_consistent(first_year: i32, last_year: i32) {
+    for year in (first_year..=last_year).filter(|&year| year != 0) {
+        for date in Date::calendar_year(year).unwrap() {
+            assert_eq!(Date::from_day_of_year(year, date.day_of_year()), date);
+            assert_eq!(Date::from_julian_day(date.to_julian_day().unwrap()), Ok(date.clone()));
+            assert_eq!(date.add_days(1).and_then(|next| next.add_days(-1)), Ok(date.clone()));
+            assert_eq!(date.succ().and_then(|next| next.pred()), Some(date.clone()));
+        }
+    }
+}
+
+#[test]
+fn test_self_consistency() {
+    assert_self_consistent(1500, 2100);
+    assert_self_consistent(-5, 5);
+}
+
+#[test]
+fn test_days_between_julian_dates() {
+    let first = Date::new(100, 1, 1);
+    let last = Date::new(500, 12, 31);
+    //401 years, and 101 of them (100, 104... 500) leap under the Julian rule
+    assert_eq!(Date::days_between_dates(&first, &last), Ok(401 * 365 + 101 - 1));
+    assert_eq!(Date::days_between_dates(&last, &first), Ok(-(401 * 365 + 101 - 1)));
+
+    //100 BC to 1 BC: 25 leap years, 97 BC... 1 BC
+    assert_eq!(Date::days_between_dates(&Date::new(-100, 1, 1), &Date::new(-1, 12, 31)), Ok(100 * 365 + 25 - 1));
+    assert_eq!(Date::days_between_dates(&Date::new(-1, 12, 31), &Date::new(1, 1, 1)), Ok(1));
+    assert_eq!(Date::days_between_dates(&Date::new(1500, 2, 28), &Date::new(1500, 3, 1)), Ok(2));
+    assert_eq!(Date::days_between_dates(&Date::new(1, 1, 1), &Date::new(1582, 10, 4)),
+               Ok((Date::new(1582, 10, 4).to_rata_die() - 1) as i32));
 
-}
\ No newline at end of file
+    for &(first, last) in &[((100, 1, 1), (500, 12, 31)), ((-753, 4, 21), (-44, 3, 15)), ((-44, 3, 15), (1200, 2, 29))] {
+        let first = Date::try_from(first).unwrap();
+        let last = Date::try_from(last).unwrap();
+        assert_eq!(Date::days_between_dates(&first, &last).map(i64::from),
+                   Ok(last.to_julian_day().unwrap() - first.to_julian_day().unwrap()));
+    }
+
+    //Spans wider than i32 days fail rather than overflow
+    assert!(Date::days_between_dates(&Date::new(2021, 1, 1), &Date::new(9_000_000, 1, 1)).is_err());
+    assert!(Date::days_between_dates(&Date::MAX, &Date::MIN).is_err());
+}
+
+#[test]
+fn test_debug() {
+    assert_eq!(format!("{:?}", Date::new(2001, 2, 28)), "Date(2001-02-28)");
+    assert_eq!(format!("{:?}", Date::new(-44, 3, 15)), "Date(-0044-03-15)");
+    assert_eq!(format!("{:?}", Date::new(2021, 13, 40)), "Date(2021-13-40)");
+    assert_eq!(format!("{:?}", Some(Date::new(1, 1, 1))), "Some(Date(0001-01-01))");
+    assert_eq!(format!("{:?}", DateError::InvalidDay(Date::new(2021, 2, 29))), "InvalidDay(Date(2021-02-29))");
+}
+
+#[test]
+fn test_snap_out_of_gap() {
+    for day in 5..=14 {
+        assert_eq!(Date::new(1582, 10, day).snap_out_of_gap(), Date::new(1582, 10, 15));
+    }
+    assert_eq!(Date::new(1582, 10, 4).snap_out_of_gap(), Date::new(1582, 10, 4));
+    assert_eq!(Date::new(1582, 10, 15).snap_out_of_gap(), Date::new(1582, 10, 15));
+    assert_eq!(Date::new(1752, 9, 10).snap_out_of_gap(), Date::new(1752, 9, 10));
+}
+
+#[test]
+fn test_weekday_histogram() {
+    assert_eq!(Date::weekday_histogram(2021), Ok([52, 52, 52, 52, 53, 52, 52]));
+    assert_eq!(Date::weekday_histogram(2024), Ok([53, 53, 52, 52, 52, 52, 52]));
+    assert_eq!(Date::weekday_histogram(2022), Ok([52, 52, 52, 52, 52, 53, 52]));
+    assert_eq!(Date::weekday_histogram(2023), Ok([52, 52, 52, 52, 52, 52, 53]));
+    assert_eq!(Date::weekday_histogram(2016), Ok([52, 52, 52, 52, 53, 53, 52]));
+    for &year in &[2021, 2024, 1582, 1500, -1] {
+        let histogram = Date::weekday_histogram(year).unwrap();
+        assert_eq!(histogram.iter().sum::<u32>() as i32, Date::year_days(year));
+        for (index, weekday) in WEEKDAYS.iter().enumerate() {
+            let count = Date::calendar_year(year).unwrap().iter()
+                .filter(|date| date.weekday_name() == Ok(*weekday)).count();
+            assert_eq!(histogram[index] as usize, count);
+        }
+    }
+    assert!(Date::weekday_histogram(0).is_err());
+}
+
+#[test]
+fn test_parse_error_position() {
+    let at = |result: Result<Date, ParseError>| result.map_err(|error| error.position);
+    assert_eq!(at(Date::parse("2021-1x-22")), Err(6));
+    assert_eq!(at(Date::parse("2021-13-22")), Err(5));
+    assert_eq!(at(Date::parse("2021-07-2x")), Err(9));
+    assert_eq!(at(Date::parse("2021-02-29")), Err(8));
+    assert_eq!(at(Date::parse("2021-07")), Err(7));
+    assert_eq!(at(Date::parse("-0044-13-15")), Err(6));
+    assert_eq!(at(Date::parse("0000-01-01")), Err(0));
+    assert_eq!(at(Date::parse("20211322")), Err(4));
+    assert_eq!(at(Date::parse("202107221")), Err(8));
+    assert_eq!(at(Date::parse("7/x2/21")), Err(2));
+    assert_eq!(at(Date::parse("07/32/21")), Err(3));
+    assert_eq!(at(Date::parse("July 2x, 2021")), Err(6));
+    assert_eq!(at(Date::parse("July 32, 2021")), Err(5));
+    assert_eq!(at(Date::parse("July 22 2021")), Err(7));
+    assert_eq!(at(Date::parse_exact("22 Jly 2021", "%d %b %Y")), Err(3));
+    assert_eq!(at(Date::parse_exact("2021-13-01", "%Y-%m-%d")), Err(5));
+    assert_eq!(at(Date::parse_julian_day("24515x5")), Err(5));
+    assert_eq!(at(Date::parse_compact("2021ñ722")), Err(4));
+    assert_eq!(at(Date::parse_exact("ñ2021-13-01", "ñ%Y-%m-%d")), Err(6));
+
+    let error = Date::parse("2021-1x-22").unwrap_err();
+    assert_eq!(error.to_string(), "2021-1x-22: Invalid date, expected YYYY-MM-DD (at character 6)");
+}
+
+#[test]
+fn test_validate() {
+    for &(year, month, day) in &[(1979, 2, 29), (1979, 1, 0), (0, 1, 1), (1, 1, 41), (-4, 15, 1), (1582, 10, 11)] {
+        assert!(Date::validate(year, month, day).is_err());
+        assert_eq!(Date::validate(year, month, day), Date::new(year, month, day).is_valid());
+    }
+    assert_eq!(Date::validate(2000, 2, 29), Ok(()));
+    assert_eq!(Date::validate(1582, 10, 15), Ok(()));
+    assert_eq!(Date::validate(0, 13, 40), Err("Year 0 does not exist".into()));
+    assert_eq!(Date::validate(1582, 10, 10), Err("October 10, 1582 does not exist".into()));
+}
+
+#[test]
+fn test_midpoint() {
+    assert_eq!(Date::midpoint(&Date::new(2021, 7, 1), &Date::new(2021, 7, 31)), Ok(Date::new(2021, 7, 16)));
+    assert_eq!(Date::midpoint(&Date::new(2021, 7, 1), &Date::new(2021, 7, 4)), Ok(Date::new(2021, 7, 2)));
+    assert_eq!(Date::midpoint(&Date::new(2021, 7, 4), &Date::new(2021, 7, 1)), Ok(Date::new(2021, 7, 3)));
+    assert_eq!(Date::midpoint(&Date::new(2021, 7, 4), &Date::new(2021, 7, 4)), Ok(Date::new(2021, 7, 4)));
+    assert_eq!(Date::midpoint(&Date::new(1582, 10, 1), &Date::new(1582, 10, 21)), Ok(Date::new(1582, 10, 16)));
+    assert_eq!(Date::midpoint(&Date::new(1582, 10, 3), &Date::new(1582, 10, 16)), Ok(Date::new(1582, 10, 4)));
+    assert!(Date::midpoint(&Date::MIN, &Date::MAX).is_ok());
+    assert!(Date::midpoint(&Date::new(2021, 2, 29), &Date::new(2021, 7, 4)).is_err());
+}
+
+#[test]
+fn test_parse_padded() {
+    assert_eq!(Date::parse("  2021-07-22 \n").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse("\t20210722").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse(" 07/22/21 ").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse(" July 22, 2021 ").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse_compact(" 20210722").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse_with_pivot("07/22/21\n", 69).unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse_exact(" 22.07.2021 ", "%d.%m.%Y").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse_julian_day(" 2459418 ").unwrap(), (2021, 7, 22));
+
+    assert_eq!(Date::parse("2021-07-22T13:00:00Z").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse(" 2021-07-22T13:00:00+02:00 ").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse("2021-07-22Z").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse("-0044-03-15T12:00").unwrap(), (-44, 3, 15));
+    assert!(Date::parse("2021-07-22ZZ").is_err());
+    assert!(Date::parse("T2021-07-22").is_err());
+
+    //positions still count the padding
+    assert_eq!(Date::parse("  2021-1x-22").map_err(|error| error.position), Err(8));
+    assert_eq!(Date::parse_compact("   20211322").map_err(|error| error.position), Err(7));
+}
+
+#[test]
+fn test_previous_business_day() {
+    //Monday, July 26, 2021
+    assert_eq!(Date::new(2021, 7, 26).previous_business_day(None).unwrap(), (2021, 7, 23));
+    assert_eq!(Date::new(2021, 7, 25).previous_business_day(None).unwrap(), (2021, 7, 23));
+    assert_eq!(Date::new(2021, 7, 23).previous_business_day(None).unwrap(), (2021, 7, 22));
+    assert_eq!(Date::new(1582, 10, 18).previous_business_day(None).unwrap(), (1582, 10, 15));
+    assert_eq!(Date::new(1582, 10, 15).previous_business_day(None).unwrap(), (1582, 10, 4));
+
+    //Good Friday and Easter Monday 2021 around the weekend
+    let easter = vec![Date::new(2021, 4, 2), Date::new(2021, 4, 5)];
+    assert_eq!(Date::new(2021, 4, 6).previous_business_day(Some(&easter)).unwrap(), (2021, 4, 1));
+    assert_eq!(Date::new(2021, 4, 5).previous_business_day(Some(&easter)).unwrap(), (2021, 4, 1));
+    assert_eq!(Date::new(2021, 4, 2).previous_business_day(Some(&easter)).unwrap(), (2021, 4, 1));
+    assert_eq!(Date::new(2021, 4, 1).previous_business_day(Some(&easter)).unwrap(), (2021, 3, 31));
+    assert!(Date::new(2021, 2, 30).previous_business_day(None).is_err());
+
+    //A calendar of nothing but holidays gives up after a year instead of scanning every date
+    struct EveryDay;
+    impl HolidayProvider for EveryDay {
+        fn is_holiday(&self, _: &Date) -> bool {
+            true
+        }
+    }
+    let near_min = Date::MIN.add_days_i64(1000).unwrap();
+    let near_max = Date::MAX.add_days_i64(-1000).unwrap();
+    assert!(near_min.previous_business_day(Some(&EveryDay)).is_err());
+    assert!(near_max.add_business_days(1, Some(&EveryDay)).is_err());
+    let date = Date::new(2021, 7, 22);
+    assert_eq!(date.previous_business_day(Some(&EveryDay)),
+               Err("July 22, 2021: No business day within a year".into()));
+    assert!(date.add_business_days(1, Some(&EveryDay)).is_err());
+    assert!(date.add_business_days(-10, Some(&EveryDay)).is_err());
+}
+
+#[test]
+fn test_year_fraction() {
+    assert_eq!(Date::new(2020, 1, 1).year_fraction(), Ok(0.0));
+    assert_eq!(Date::new(2020, 7, 2).year_fraction(), Ok(0.5));
+    assert_eq!(Date::new(2020, 12, 31).year_fraction(), Ok(365.0 / 366.0));
+    assert_eq!(Date::new(2021, 12, 31).year_fraction(), Ok(364.0 / 365.0));
+    assert_eq!(Date::new(1582, 10, 15).year_fraction(), Ok(277.0 / 355.0));
+    assert_eq!(Date::new(1582, 12, 31).year_fraction(), Ok(354.0 / 355.0));
+    assert_eq!(Date::new(-1, 1, 1).year_fraction(), Ok(0.0));
+    assert!(Date::new(1582, 10, 10).year_fraction().is_err());
+}
+
+#[test]
+fn test_add_business_days() {
+    //Thursday, July 22, 2021
+    let thursday = Date::new(2021, 7, 22);
+    assert_eq!(thursday.add_business_days(0, None).unwrap(), (2021, 7, 22));
+    assert_eq!(thursday.add_business_days(1, None).unwrap(), (2021, 7, 23));
+    assert_eq!(thursday.add_business_days(2, None).unwrap(), (2021, 7, 26));
+    assert_eq!(thursday.add_business_days(10, None).unwrap(), (2021, 8, 5));
+    assert_eq!(thursday.add_business_days(-4, None).unwrap(), (2021, 7, 16));
+    assert_eq!(Date::new(2021, 7, 24).add_business_days(1, None).unwrap(), (2021, 7, 26));
+    assert_eq!(Date::new(2021, 7, 24).add_business_days(-1, None).unwrap(), (2021, 7, 23));
+    assert_eq!(Date::new(2021, 7, 24).add_business_days(0, None).unwrap(), (2021, 7, 24));
+
+    //Good Friday and Easter Monday 2021
+    let easter = vec![Date::new(2021, 4, 2), Date::new(2021, 4, 5)];
+    assert_eq!(Date::new(2021, 4, 1).add_business_days(1, Some(&easter)).unwrap(), (2021, 4, 6));
+    assert_eq!(Date::new(2021, 3, 31).add_business_days(3, Some(&easter)).unwrap(), (2021, 4, 7));
+    assert_eq!(Date::new(2021, 4, 6).add_business_days(-2, Some(&easter)).unwrap(), (2021, 3, 31));
+    assert_eq!(Date::new(2021, 4, 2).add_business_days(1, Some(&easter)).unwrap(), (2021, 4, 6));
+    assert!(Date::new(2021, 2, 30).add_business_days(0, None).is_err());
+    assert!(Date::MAX.add_business_days(1, None).is_err());
+}
+
+#[test]
+fn test_ordinal_iso() {
+    assert_eq!(Date::new(2021, 7, 22).ordinal_iso(), Ok("2021-203".to_string()));
+    assert_eq!(Date::new(2021, 1, 1).ordinal_iso(), Ok("2021-001".to_string()));
+    assert_eq!(Date::new(-44, 3, 15).ordinal_iso(), Ok("-0044-074".to_string()));
+    assert_eq!(Date::parse("2021-203").unwrap(), (2021, 7, 22));
+    assert_eq!(Date::parse(" 2020-366T00:00Z").unwrap(), (2020, 12, 31));
+    assert_eq!(Date::parse("1582-278").unwrap(), (1582, 10, 15));
+    for date in &[Date::new(2021, 7, 22), Date::new(2020, 12, 31), Date::new(1582, 10, 4),
+                  Date::new(1582, 12, 31), Date::new(-44, 3, 15), Date::new(12_345, 6, 7)] {
+        assert_eq!(&Date::parse(&date.ordinal_iso().unwrap()).unwrap(), date);
+    }
+    assert_eq!(Date::from_ordinal(2021, 203), Ok(Date::new(2021, 7, 22)));
+
+    let at = |result: Result<Date, ParseError>| result.map_err(|error| error.position);
+    assert_eq!(at(Date::parse("2021-366")), Err(5));
+    assert_eq!(at(Date::parse("1582-356")), Err(5));
+    assert_eq!(at(Date::parse("2021-000")), Err(5));
+    assert_eq!(at(Date::parse("2021-2x3")), Err(6));
+    assert_eq!(at(Date::parse("-0000-100")), Err(1));
+    assert_eq!(at(Date::parse("2021-07")), Err(7));
+    assert!(Date::new(2021, 2, 29).ordinal_iso().is_err());
+}
+
+#[test]
+fn test_parse_bc_agrees() {
+    let ides = Date::parse("-0044-03-15").unwrap();
+    assert_eq!(ides, (-44, 3, 15));
+    assert_eq!(Date::parse_prose("15 March 44 BC"), Ok(ides.clone()));
+    assert_eq!(Date::parse("15 March 44 BC"), Ok(ides.clone()));
+    assert_eq!(Date::parse("March 15, 44 BC"), Ok(ides.clone()));
+    assert_eq!(Date::parse("-00440315"), Ok(ides.clone()));
+    assert_eq!(Date::parse(&ides.to_string()), Ok(ides));
+    assert_eq!(Date::parse("1 January 1 BC").unwrap(), (-1, 1, 1));
+    assert_eq!(Date::parse("22 july 2021").unwrap(), (2021, 7, 22));
+
+    let at = |result: Result<Date, ParseError>| result.map_err(|error| error.position);
+    assert_eq!(at(Date::parse_prose("15 Marc 44 BC")), Err(3));
+    assert_eq!(at(Date::parse_prose("15 March, 44 BC")), Err(3));
+    assert_eq!(at(Date::parse("30 February 2021")), Err(0));
+    assert_eq!(at(Date::parse("1 March 0 BC")), Err(8));
+}
+
+#[test]
+fn test_month_length() {
+    let leap: Vec<u8> = (1..=12).map(|month| Date::month_length(2024, month).unwrap()).collect();
+    assert_eq!(leap, vec![31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]);
+    assert_eq!(Date::month_length(2023, 2), Ok(28));
+    assert_eq!(Date::month_length(1900, 2), Ok(28));
+    assert_eq!(Date::month_length(1500, 2), Ok(29));
+    assert_eq!(Date::month_length(1582, 10), Ok(21));
+    assert_eq!(Date::month_length(1582, 9), Ok(30));
+    assert_eq!(Date::month_length(1583, 10), Ok(31));
+    assert_eq!(Date::new(1582, 10, 1).end_of_month(), Ok(Date::new(1582, 10, 31)));
+    assert!(Date::month_length(2021, 13).is_err());
+    assert!(Date::month_length(0, 1).is_err());
+    assert_eq!(Month::January.days_in(0), 0);
+
+    //Validation and end_of_month agree with month_length on where each month ends
+    for &year in &[2024, 2023, 1900, 1500, 1582, -1] {
+        for month in 1..=12 {
+            let end = Date::new(year, month, 1).end_of_month().unwrap();
+            let skipped = if (year, month) == (1582, 10) { 10 } else { 0 };
+            assert_eq!(end.day, Date::month_length(year, month).unwrap() + skipped);
+            assert!(Date::new(year, month, end.day + 1).is_valid().is_err());
+        }
+    }
+}
+
+#[test]
+fn test_with_fields() {
+    let date = Date::new(2020, 2, 29);
+    assert_eq!(date.with_year(2024), Ok(Date::new(2024, 2, 29)));
+    assert_eq!(date.with_year(2021), Ok(Date::new(2021, 2, 28)));
+    assert_eq!(date.with_year(-44), Ok(Date::new(-44, 2, 28)));
+    assert_eq!(Date::new(2021, 10, 10).with_year(1582), Ok(Date::new(1582, 10, 15)));
+    assert!(date.with_year(0).is_err());
+
+    assert_eq!(Date::new(2021, 1, 31).with_month(2), Ok(Date::new(2021, 2, 28)));
+    assert_eq!(Date::new(2020, 1, 31).with_month(2), Ok(Date::new(2020, 2, 29)));
+    assert_eq!(Date::new(2021, 1, 31).with_month(4), Ok(Date::new(2021, 4, 30)));
+    assert_eq!(Date::new(2021, 1, 31).with_month(12), Ok(Date::new(2021, 12, 31)));
+    assert_eq!(Date::new(1582, 9, 12).with_month(10), Ok(Date::new(1582, 10, 15)));
+    assert!(date.with_month(13).is_err());
+    assert!(date.with_month(0).is_err());
+
+    assert_eq!(date.with_day(1), Ok(Date::new(2020, 2, 1)));
+    assert!(date.with_day(30).is_err());
+    assert!(date.with_day(0).is_err());
+    assert!(Date::new(1582, 10, 1).with_day(10).is_err());
+    assert!(Date::new(2021, 2, 29).with_day(1).is_err());
+}
+
+#[test]
+fn test_check_gap() {
+    match Date::new(1582, 10, 10).check() {
+        Err(DateError::SkippedByReform { date, previous, next }) => {
+            assert_eq!(date, Date::new(1582, 10, 10));
+            assert_eq!(previous, Date::new(1582, 10, 4));
+            assert_eq!(next, Date::new(1582, 10, 15));
+        }
+        other => panic!("Expected a skipped day, got {:?}", other),
+    }
+    assert!(!Date::new(1582, 10, 14).check().unwrap_err().is_structural());
+    assert!(Date::new(1582, 10, 32).check().unwrap_err().is_structural());
+    assert!(Date::new(2021, 2, 29).check().unwrap_err().is_structural());
+    assert!(Date::new(2021, 13, 1).check().unwrap_err().is_structural());
+    assert!(Date::new(0, 1, 1).check().unwrap_err().is_structural());
+    assert_eq!(Date::new(1582, 10, 15).check(), Ok(()));
+    assert_eq!(Date::new(1582, 10, 5).is_valid(), Err("October 5, 1582 does not exist".to_string()));
+}
+
+#[test]
+fn test_julian_day_round_trip_1582() {
+    //October 4, 1582 (Julian) and October 15, 1582 (Gregorian) are consecutive days
+    assert_eq!(Date::new(1582, 10, 4).to_julian_day(), Ok(2_299_160));
+    assert_eq!(Date::new(1582, 10, 15).to_julian_day(), Ok(2_299_161));
+    let start = Date::new(1582, 1, 1).to_julian_day().unwrap();
+    let end = Date::new(1583, 1, 1).to_julian_day().unwrap();
+    assert_eq!(end - start, 355);
+    let mut julian_day = start;
+    for month in 1..=12 {
+        for day in 1..=31 {
+            let date = Date::new(1582, month, day);
+            if date.is_valid().is_err() {
+                continue;
+            }
+            assert_eq!(date.to_julian_day(), Ok(julian_day), "{}", date);
+            assert_eq!(Date::from_julian_day(julian_day), Ok(date));
+            julian_day += 1;
+        }
+    }
+    assert_eq!(julian_day, end);
+}
+
+#[test]
+fn test_weekday_fast_path() {
+    assert_eq!(Date::new(3000, 1, 1).weekday(), Ok(Weekday::Wednesday));
+    assert_eq!(Date::new(3000, 7, 22).weekday(), Ok(Weekday::Tuesday));
+    //the days counted one by one from the epoch give the same weekdays
+    let slow = |date: &Date| Date::days_between_dates(&Date::default(), date)
+        .map(|days| Weekday::from_monday(Date::epoch_weekday().days_from_monday() + days));
+    for year in (2995..=3005).chain(1580..=1585).chain(-5..=5).filter(|&year| year != 0) {
+        for month in 1..=12 {
+            let date = Date::new(year, month, 28);
+            assert_eq!(date.weekday(), slow(&date), "{}", date);
+        }
+    }
+    assert!(Date::MAX.weekday().is_ok());
+    assert!(Date::MIN.weekday().is_ok());
+    assert!(Date::new(1582, 10, 10).weekday().is_err());
+}
+
+#[test]
+fn test_proleptic_year() {
+    assert_eq!(Date::new(-1, 7, 1).proleptic_year(), 0);
+    assert_eq!(Date::new(-2, 7, 1).proleptic_year(), -1);
+    assert_eq!(Date::new(-44, 3, 15).proleptic_year(), -43);
+    assert_eq!(Date::new(1, 1, 1).proleptic_year(), 1);
+    assert_eq!(Date::new(2021, 7, 22).proleptic_year(), 2021);
+    assert_eq!(Date::MIN.proleptic_year(), i32::MIN + 1);
+}
+
+#[test]
+fn test_year_representable() {
+    let max = i32::MAX as i64;
+    let min = i32::MIN as i64;
+    assert!(Date::is_year_representable(max));
+    assert!(!Date::is_year_representable(max + 1));
+    assert!(Date::is_year_representable(min));
+    assert!(!Date::is_year_representable(min - 1));
+    assert!(!Date::is_year_representable(0));
+    assert!(Date::is_year_representable(-1));
+
+    assert_eq!(Date::try_new_i64(max, 12, 31), Ok(Date::MAX));
+    assert_eq!(Date::try_new_i64(max + 1, 1, 1), Err("2147483648: Year out of range".to_string()));
+    assert_eq!(Date::try_new_i64(min - 1, 12, 31), Err("-2147483649: Year out of range".to_string()));
+    assert_eq!(Date::try_new_i64(0, 1, 1), Err("Year 0 does not exist".to_string()));
+    assert!(Date::try_new_i64(2021, 2, 29).is_err());
+
+    assert!(Date::MAX.add_days(1).is_err());
+    assert!(Date::MIN.add_days(-1).is_err());
+    assert_eq!(Date::new(i32::MAX, 12, 30).add_days(1), Ok(Date::MAX));
+    assert!(Date::MAX.add_days_i64(1).is_err());
+    assert!(Date::from_julian_day(i64::MAX).is_err());
+}
+
+#[test]
+fn test_count_weekday_between() {
+    let (first, last) = (Date::new(2021, 8, 1), Date::new(2021, 9, 1));
+    assert_eq!(Date::count_sundays_between(&first, &last), Ok(5));
+    assert_eq!(Date::count_saturdays_between(&first, &last), Ok(4));
+    assert_eq!(Date::count_weekday_between(&first, &last, Weekday::Tuesday), Ok(5));
+    assert_eq!(Date::count_sundays_between(&last, &first), Ok(0));
+    assert_eq!(Date::count_sundays_between(&Date::new(1582, 10, 1), &Date::new(1582, 11, 1)), Ok(3));
+
+    let (first, last) = (Date::new(2020, 1, 1), Date::new(2021, 1, 1));
+    let histogram = Date::weekday_histogram(2020).unwrap();
+    for (index, &count) in histogram.iter().enumerate() {
+        let weekday = Weekday::from_monday(index as i32);
+        let is_weekday = |date: &Date| date.weekday() == Ok(weekday);
+        assert_eq!(Date::count_weekday_between(&first, &last, weekday), Ok(count));
+        assert_eq!(Date::count_matching(first.clone(), last.clone(), is_weekday), Ok(count));
+    }
+    assert_eq!(Date::count_sundays_between(&first, &last), Ok(histogram[6]));
+    assert_eq!(Date::count_saturdays_between(&first, &last), Ok(histogram[5]));
+    assert!(Date::count_sundays_between(&Date::new(2021, 2, 30), &last).is_err());
+}
+
+#[test]
+fn test_ordinal_tuple() {
+    assert_eq!(Date::new(2021, 7, 22).to_ordinal_tuple(), Ok((2021, 203)));
+    assert_eq!(Date::new(1582, 10, 15).to_ordinal_tuple(), Ok((1582, 278)));
+    assert!(Date::new(2021, 0, 1).to_ordinal_tuple().is_err());
+    assert!(Date::new(2021, 13, 1).to_ordinal_tuple().is_err());
+    assert!(Date::new(1582, 10, 10).to_ordinal_tuple().is_err());
+    for &year in &[2020, 1582, -1] {
+        let days = Date::calendar_year(year).unwrap();
+        for (index, date) in days.iter().enumerate() {
+            assert_eq!(date.to_ordinal_tuple(), Ok((year, index as i32 + 1)));
+            assert_eq!(Date::from_ordinal_tuple(date.to_ordinal_tuple().unwrap()).as_ref(), Ok(date));
+        }
+    }
+    assert!(Date::from_ordinal_tuple((2021, 366)).is_err());
+    assert!(Date::from_ordinal_tuple((1582, 356)).is_err());
+    assert!(Date::from_ordinal_tuple((2021, 0)).is_err());
+    assert!(Date::from_ordinal_tuple((2021, -1)).is_err());
+    assert!(Date::from_ordinal_tuple((2021, 70_000)).is_err());
+    assert!(Date::from_ordinal_tuple((0, 1)).is_err());
+}
+
+#[test]
+fn test_iso_week_year() {
+    assert_eq!(Date::new(2021, 7, 22).iso_week_year(), Ok(2021));
+    assert_eq!(Date::new(2019, 12, 31).iso_week_year(), Ok(2020));
+    assert_eq!(Date::new(2024, 12, 31).iso_week_year(), Ok(2025));
+    assert_eq!(Date::new(2020, 12, 31).iso_week_year(), Ok(2020));
+    assert_eq!(Date::new(2021, 1, 1).iso_week_year(), Ok(2020));
+    assert_eq!(Date::new(2022, 1, 1).iso_week_year(), Ok(2021));
+    assert_eq!(Date::new(2023, 1, 1).iso_week_year(), Ok(2022));
+    assert_eq!(Date::new(2024, 1, 1).iso_week_year(), Ok(2024));
+    assert!(Date::new(2021, 2, 29).iso_week_year().is_err());
+}
+
+#[test]
+fn test_days_of_month() {
+    let days: Vec<Date> = Date::days_of_month(2021, 7).unwrap().collect();
+    assert_eq!(days.len(), 31);
+    assert_eq!(days[0], Date::new(2021, 7, 1));
+    assert_eq!(days[30], Date::new(2021, 7, 31));
+    for month in 1..=12 {
+        let length = Date::month_length(2024, month).unwrap() as usize;
+        assert_eq!(Date::days_of_month(2024, month).unwrap().count(), length);
+    }
+    let october: Vec<u8> = Date::days_of_month(1582, 10).unwrap().map(|date| date.day).collect();
+    assert_eq!(october.len(), 21);
+    assert_eq!(&october[3..5], &[4, 15]);
+    assert_eq!(Date::days_of_month(i32::MAX, 12).unwrap().count(), 31);
+    assert!(Date::days_of_month(2021, 13).is_err());
+    assert!(Date::days_of_month(0, 1).is_err());
+}
+
+#[test]
+fn test_has_time_component() {
+    let date = Date::parse("2021-07-22T13:00:00Z").unwrap();
+    assert!(!date.has_time_component());
+    assert_eq!(date, Date::new(2021, 7, 22));
+    assert_eq!(Date::from_unix_seconds(1_626_958_800), Date::from_unix_seconds(1_626_912_000));
+}
+
+#[test]
+fn test_parse_all() {
+    let text = "Released 2021-07-22, patched on 2021-08-03 and again (2022-01-15).";
+    assert_eq!(Date::parse_all(text), vec![Date::new(2021, 7, 22), Date::new(2021, 8, 3), Date::new(2022, 1, 15)]);
+    assert_eq!(Date::parse_all("2021-07-22"), vec![Date::new(2021, 7, 22)]);
+    assert_eq!(Date::parse_all("1582-10-04 then 1582-10-10 then 1582-10-15"),
+               vec![Date::new(1582, 10, 4), Date::new(1582, 10, 15)]);
+    assert_eq!(Date::parse_all("from 2021-07-22T13:00:00Z to 2021-07-23/2021-07-24"),
+               vec![Date::new(2021, 7, 22), Date::new(2021, 7, 23), Date::new(2021, 7, 24)]);
+    assert_eq!(Date::parse_all("ñ 2021-07-22 ñ"), vec![Date::new(2021, 7, 22)]);
+
+    assert!(Date::parse_all("Call 555-1234-5678 or 2021-02-30, 20210722, 12021-07-22, 2021-07-223").is_empty());
+    assert!(Date::parse_all("2021-7-22, 21-07-22, 2021/07/22, 0000-01-01").is_empty());
+    assert!(Date::parse_all("").is_empty());
+    assert!(Date::parse_all("2021").is_empty());
+}
+
+#[test]
+fn test_add_days_in() {
+    let britain = Calendar::BRITAIN;
+    assert_eq!(Date::new(1752, 9, 2).add_days_in(1, &britain), Ok(Date::new(1752, 9, 14)));
+    assert_eq!(Date::new(1752, 9, 14).add_days_in(-1, &britain), Ok(Date::new(1752, 9, 2)));
+    assert_eq!(Date::new(1752, 9, 1).add_days_in(30, &britain), Ok(Date::new(1752, 10, 12)));
+    assert_eq!(Date::new(1752, 10, 12).add_days_in(-30, &britain), Ok(Date::new(1752, 9, 1)));
+    assert_eq!(Date::new(1752, 9, 2).add_days_in(0, &britain), Ok(Date::new(1752, 9, 2)));
+    assert_eq!(Date::new(1752, 9, 14).add_days_in(5, &britain), Ok(Date::new(1752, 9, 19)));
+    assert_eq!(Date::new(1582, 10, 4).add_days_in(1, &britain), Ok(Date::new(1582, 10, 15)));
+    assert!(Date::new(1752, 9, 10).add_days_in(1, &britain).is_err());
+
+    let rome = Calendar::ROME;
+    assert_eq!(Date::new(1752, 9, 2).add_days_in(1, &rome), Ok(Date::new(1752, 9, 3)));
+    assert_eq!(Date::new(1582, 10, 4).add_days_in(1, &rome), Ok(Date::new(1582, 10, 15)));
+    assert_eq!(Date::new(1582, 10, 15).add_days_in(-1, &rome), Ok(Date::new(1582, 10, 4)));
+}
+
+#[test]
+fn test_expanded_date() {
+    assert_eq!(Date::new(2021, 7, 22).expanded_date(), "2021-07-22");
+    assert_eq!(Date::new(12_021, 7, 22).expanded_date(), "+12021-07-22");
+    assert_eq!(Date::new(10_000, 1, 1).expanded_date(), "+10000-01-01");
+    assert_eq!(Date::new(9999, 12, 31).expanded_date(), "9999-12-31");
+    assert_eq!(Date::new(1, 1, 1).expanded_date(), "0001-01-01");
+    assert_eq!(Date::new(-1, 1, 1).expanded_date(), "-0001-01-01");
+    assert_eq!(Date::new(-2, 1, 1).expanded_date(), "-0002-01-01");
+    assert_eq!(Date::new(-44, 3, 15).expanded_date(), "-0044-03-15");
+    assert_eq!(Date::new(-12_021, 7, 22).expanded_date(), "-12021-07-22");
+    assert_eq!(Date::MAX.expanded_date(), "+2147483647-12-31");
+    assert_eq!(Date::MIN.expanded_date(), "-2147483648-01-01");
+
+    //Same BC years as compact and parse, so the output reads back
+    for date in [Date::new(2021, 7, 22), Date::new(12_021, 7, 22), Date::new(-1, 1, 1),
+                 Date::new(-44, 3, 15), Date::new(-12_021, 7, 22), Date::new(1582, 10, 15),
+                 Date::new(1_000_000_000, 1, 1), Date::new(-1_000_000_000, 1, 1), Date::MAX, Date::MIN] {
+        assert_eq!(Date::parse(&date.expanded_date()), Ok(date.clone()));
+    }
+    assert_eq!(Date::parse("+12021-07-22"), Ok(Date::new(12_021, 7, 22)));
+    assert_eq!(Date::parse("+12021-203"), Ok(Date::new(12_021, 7, 22)));
+    assert!(Date::parse("+-2021-07-22").is_err());
+    assert_eq!(Date::parse("+2147483648-01-01").map_err(|error| error.position), Err(1));
+    assert_eq!(Date::parse("-2147483649-203").map_err(|error| error.position), Err(1));
+    assert_eq!(Date::parse("+99999999999-01-01").map_err(|error| error.position), Err(11));
+}
+
+#[test]
+fn test_closest() {
+    let holidays = [Date::new(2021, 12, 25), Date::new(2021, 1, 1), Date::new(2021, 7, 4), Date::new(2021, 10, 31)];
+    assert_eq!(Date::new(2021, 7, 22).closest(&holidays), Some(&holidays[2]));
+    assert_eq!(Date::new(2021, 6, 1).closest(&holidays), Some(&holidays[2]));
+    assert_eq!(Date::new(2021, 12, 31).closest(&holidays), Some(&holidays[0]));
+    assert_eq!(Date::new(2021, 7, 4).closest(&holidays), Some(&holidays[2]));
+
+    //August 18 is 45 days after July 4 and 45 days before October 2
+    let tie = [Date::new(2021, 10, 2), Date::new(2021, 7, 4)];
+    assert_eq!(Date::new(2021, 8, 18).closest(&tie), Some(&tie[1]));
+    assert_eq!(Date::new(2021, 8, 19).closest(&tie), Some(&tie[0]));
+    assert_eq!(Date::new(1582, 10, 4).closest(&[Date::new(1582, 10, 15), Date::new(1582, 9, 30)]),
+               Some(&Date::new(1582, 10, 15)));
+
+    assert_eq!(Date::new(2021, 7, 22).closest(&[]), None);
+    assert_eq!(Date::new(2021, 7, 22).closest(&[Date::new(2021, 7, 23), Date::new(2021, 2, 30)]),
+               Some(&Date::new(2021, 7, 23)));
+    assert_eq!(Date::new(2021, 2, 30).closest(&holidays), None);
+
+    //Candidates millions of years away still compare, however far apart
+    let far = [Date::MAX, Date::new(9_000_000, 1, 1), Date::new(-7_000_000, 1, 1)];
+    assert_eq!(Date::new(2021, 1, 1).closest(&far), Some(&far[2]));
+    assert_eq!(Date::MAX.closest(&far[1..]), Some(&far[1]));
+    assert_eq!(Date::MIN.closest(&far), Some(&far[2]));
+}
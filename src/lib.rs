@@ -0,0 +1,3 @@
+#![cfg_attr(feature = "step", feature(step_trait))]
+
+pub mod date;
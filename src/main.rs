@@ -1,4 +1,4 @@
-mod date;
+use date::date;
 
 fn main() {
     let d0 = date::Date::new(2000,2, 29);